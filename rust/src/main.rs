@@ -3,6 +3,7 @@ use utils::print_time_results;
 mod day_template;
 mod solver;
 mod utils;
+mod year_2015;
 mod year_2018;
 mod year_2020;
 mod year_2021;
@@ -14,6 +15,7 @@ struct Args {
     day: usize,
     year: usize,
     runs: usize,
+    threads: usize,
 }
 
 fn main() {
@@ -21,6 +23,7 @@ fn main() {
         day: 0, // 0 implies run all
         year: 2022,
         runs: 1,
+        threads: 0, // 0 implies one per core
     };
 
     let raw_args: Vec<String> = std::env::args().collect();
@@ -37,7 +40,12 @@ fn main() {
         args.year = year;
     }
 
+    if let Some(threads) = raw_args.get(4).and_then(|r| r.parse::<usize>().ok()) {
+        args.threads = threads;
+    }
+
     println!("{args:?}");
+    utils::parallel::set_threads(args.threads);
     if args.day == 0 {
         let results = solver::run_all(args.year, args.runs);
         print_time_results(results, args.runs)
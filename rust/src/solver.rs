@@ -1,6 +1,8 @@
 use std::time::Instant;
 
-use crate::{utils::DayPerfMetric, year_2018, year_2020, year_2021, year_2022, year_2023};
+use crate::{
+    utils::DayPerfMetric, year_2015, year_2018, year_2020, year_2021, year_2022, year_2023,
+};
 
 pub trait Solver {
     fn get_input(&self) -> &'static str;
@@ -12,6 +14,13 @@ pub trait Solver {
 #[allow(clippy::box_default)]
 pub fn get_solver(year: usize, day: usize) -> Option<Box<dyn Solver>> {
     match (year, day) {
+        (2015, 1) => Some(Box::new(year_2015::day_01::Solution::default())),
+        (2015, 2) => Some(Box::new(year_2015::day_02::Solution::default())),
+        (2015, 3) => Some(Box::new(year_2015::day_03::Solution::default())),
+        (2015, 4) => Some(Box::new(year_2015::day_04::Solution::default())),
+
+        (2015, 25) => Some(Box::new(year_2015::day_25::Solution::default())),
+
         (2018, 5) => Some(Box::new(year_2018::day_05::Solution::default())),
 
         (2020, 1) => Some(Box::new(year_2020::day_01::Solution::default())),
@@ -43,11 +52,13 @@ pub fn get_solver(year: usize, day: usize) -> Option<Box<dyn Solver>> {
         (2022, 16) => Some(Box::new(year_2022::day_16::Solution::default())),
         (2022, 17) => Some(Box::new(year_2022::day_17::Solution::default())),
         (2022, 18) => Some(Box::new(year_2022::day_18::Solution::default())),
-        // (2022, 19) => Some(Box::new(year_2022::day_19::Solution::default())),
+        (2022, 19) => Some(Box::new(year_2022::day_19::Solution::default())),
         (2022, 20) => Some(Box::new(year_2022::day_20::Solution::default())),
         (2022, 21) => Some(Box::new(year_2022::day_21::Solution::default())),
 
         (2023, 1) => Some(Box::new(year_2023::day_01::Solution::default())),
+        (2023, 2) => Some(Box::new(year_2023::day_02::Solution::default())),
+        (2023, 3) => Some(Box::new(year_2023::day_03::Solution::default())),
         _ => None,
     }
 }
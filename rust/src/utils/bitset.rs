@@ -0,0 +1,307 @@
+use std::{
+    fmt::{self, Debug},
+    ops::BitOr,
+};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// The indices of the set bits in `words`, in increasing order.
+#[derive(Debug, Clone)]
+pub struct Bits<'a> {
+    words: &'a [u64],
+    offset: usize,
+    current: u64,
+}
+
+impl<'a> Bits<'a> {
+    fn new(words: &'a [u64]) -> Self {
+        Self {
+            words,
+            offset: 0,
+            current: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl Iterator for Bits<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.offset += 1;
+            self.current = *self.words.get(self.offset)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // clear the lowest set bit
+        self.current &= self.current - 1;
+        Some(self.offset * WORD_BITS + bit)
+    }
+}
+
+/// A set of small integers packed into `N` words, so it holds values below
+/// `N * 64`. It's `Copy` and cheap to hash, which makes it a good key for
+/// memo tables over "which of these things have been used" states.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BitSet<const N: usize> {
+    words: [u64; N],
+}
+
+impl<const N: usize> Default for BitSet<N> {
+    fn default() -> Self {
+        Self { words: [0; N] }
+    }
+}
+
+impl<const N: usize> Debug for BitSet<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize> BitSet<N> {
+    pub const CAPACITY: usize = N * WORD_BITS;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value`, returning whether it was newly added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` doesn't fit in the set.
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(
+            value < Self::CAPACITY,
+            "{value} doesn't fit in a BitSet<{N}>"
+        );
+        let (word, mask) = (value / WORD_BITS, 1 << (value % WORD_BITS));
+        let added = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        added
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < Self::CAPACITY && self.words[value / WORD_BITS] & (1 << (value % WORD_BITS)) != 0
+    }
+
+    /// A copy of the set with `value` added.
+    pub fn with(mut self, value: usize) -> Self {
+        self.insert(value);
+        self
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words.iter().zip(other.words).all(|(a, b)| a & b == 0)
+    }
+
+    pub fn iter(&self) -> Bits<'_> {
+        Bits::new(&self.words)
+    }
+}
+
+impl<const N: usize> BitOr for BitSet<N> {
+    type Output = Self;
+    fn bitor(mut self, other: Self) -> Self {
+        for (a, b) in self.words.iter_mut().zip(other.words) {
+            *a |= b;
+        }
+        self
+    }
+}
+
+/// A set of integers stored one bit each, growing to fit the largest value
+/// inserted. Trailing empty words are always trimmed off, so equal sets
+/// compare and hash the same whatever they held before.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct DenseBitSet {
+    words: Vec<u64>,
+}
+
+impl Debug for DenseBitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl DenseBitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value`, returning whether it was newly added.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, mask) = (value / WORD_BITS, 1 << (value % WORD_BITS));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let added = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        added
+    }
+
+    /// Removes `value`, returning whether it was there.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, mask) = (value / WORD_BITS, 1 << (value % WORD_BITS));
+        let Some(w) = self.words.get_mut(word) else {
+            return false;
+        };
+        let removed = *w & mask != 0;
+        *w &= !mask;
+        self.trim();
+        removed
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / WORD_BITS)
+            .is_some_and(|w| w & (1 << (value % WORD_BITS)) != 0)
+    }
+
+    pub fn iter(&self) -> Bits<'_> {
+        Bits::new(&self.words)
+    }
+
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+}
+
+/// Which cells of a fixed size `D` dimensional box are occupied, one bit per
+/// cell. Coordinates are laid out like [`Grid`](super::grid::Grid), with the
+/// last axis varying fastest, so a `BitGrid<2>` is indexed `[row, col]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid<const D: usize> {
+    dims: [usize; D],
+    bits: DenseBitSet,
+}
+
+impl<const D: usize> Default for BitGrid<D> {
+    fn default() -> Self {
+        Self {
+            dims: [0; D],
+            bits: DenseBitSet::new(),
+        }
+    }
+}
+
+impl<const D: usize> BitGrid<D> {
+    /// An empty grid with the given size along each axis.
+    pub fn new(dims: [usize; D]) -> Self {
+        Self {
+            dims,
+            bits: DenseBitSet::new(),
+        }
+    }
+
+    pub fn dims(&self) -> [usize; D] {
+        self.dims
+    }
+
+    pub fn in_bounds(&self, coords: [usize; D]) -> bool {
+        coords.iter().zip(self.dims).all(|(c, d)| *c < d)
+    }
+
+    fn index(&self, coords: [usize; D]) -> Option<usize> {
+        self.in_bounds(coords).then(|| {
+            coords
+                .iter()
+                .zip(self.dims)
+                .fold(0, |index, (c, d)| index * d + c)
+        })
+    }
+
+    fn coords(&self, mut index: usize) -> [usize; D] {
+        let mut coords = [0; D];
+        for (c, d) in coords.iter_mut().zip(self.dims).rev() {
+            *c = index % d;
+            index /= d;
+        }
+        coords
+    }
+
+    /// Marks a cell occupied, returning whether it was empty before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `coords` is outside the grid.
+    pub fn insert(&mut self, coords: [usize; D]) -> bool {
+        let index = self
+            .index(coords)
+            .unwrap_or_else(|| panic!("{coords:?} is outside a {:?} grid", self.dims));
+        self.bits.insert(index)
+    }
+
+    /// Marks a cell empty, returning whether it was occupied before.
+    pub fn remove(&mut self, coords: [usize; D]) -> bool {
+        self.index(coords).is_some_and(|i| self.bits.remove(i))
+    }
+
+    /// Whether a cell is occupied. Anything outside the grid isn't.
+    pub fn contains(&self, coords: [usize; D]) -> bool {
+        self.index(coords).is_some_and(|i| self.bits.contains(i))
+    }
+
+    /// The occupied cells, in layout order.
+    pub fn iter(&self) -> impl Iterator<Item = [usize; D]> + '_ {
+        self.bits.iter().map(|i| self.coords(i))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BitSet::<2>::new();
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        set = set.with(0).with(64).with(127);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 3, 64, 127]);
+        assert!(set.contains(64));
+        assert!(!set.contains(65));
+        assert!(!set.contains(1000));
+
+        let other = BitSet::<2>::new().with(3).with(100);
+        assert_eq!((set | other).iter().count(), 5);
+        assert!(!set.is_disjoint(&other));
+        assert!(set.is_disjoint(&BitSet::new().with(1)));
+        assert_eq!(format!("{other:?}"), "{3, 100}");
+    }
+
+    #[test]
+    fn test_dense_bitset() {
+        let mut set = DenseBitSet::new();
+        for value in [1, 500, 70] {
+            set.insert(value);
+        }
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1, 70, 500]);
+
+        // shrinking back down compares equal to never having grown
+        assert!(set.remove(500));
+        assert!(!set.remove(500));
+        let mut small = DenseBitSet::new();
+        small.insert(1);
+        small.insert(70);
+        assert_eq!(set, small);
+    }
+
+    #[test]
+    fn test_bitgrid() {
+        let mut grid = BitGrid::new([3, 4, 5]);
+        assert!(grid.insert([2, 3, 4]));
+        assert!(grid.insert([0, 1, 2]));
+        assert!(!grid.insert([0, 1, 2]));
+        assert!(grid.contains([2, 3, 4]));
+        assert!(!grid.contains([2, 3, 3]));
+        assert!(!grid.contains([3, 0, 0]));
+        assert_eq!(grid.iter().collect::<Vec<_>>(), vec![[0, 1, 2], [2, 3, 4]]);
+
+        assert!(grid.remove([0, 1, 2]));
+        assert!(!grid.remove([0, 1, 2]));
+        assert_eq!(grid.iter().collect::<Vec<_>>(), vec![[2, 3, 4]]);
+    }
+}
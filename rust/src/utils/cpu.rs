@@ -0,0 +1,227 @@
+use std::{collections::HashSet, fmt::Debug, fmt::Display};
+
+/// One instruction of a puzzle's assembly language. It takes `cycles` clock
+/// cycles and only changes the machine state once the last one is over, so
+/// anything watching during those cycles still sees the old state.
+pub trait Instruction {
+    type State;
+
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, state: &mut Self::State);
+}
+
+/// Something hooked up to the machine that sees the state during every
+/// cycle, like a screen or a probe. Cycles count from 1.
+pub trait Device<S> {
+    fn tick(&mut self, cycle: usize, state: &S);
+}
+
+impl<S> Device<S> for () {
+    fn tick(&mut self, _cycle: usize, _state: &S) {}
+}
+
+/// Why [`Machine::run`] gave control back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// The program ran off the end.
+    Halted,
+    /// During this cycle, before the instruction it's part of is done.
+    Cycle(usize),
+}
+
+/// A cycle accurate machine running a program of `I`. Running can be stopped
+/// at breakpoints and picked up again from exactly the same spot, even part
+/// way through an instruction.
+#[derive(Debug, Clone)]
+pub struct Machine<I: Instruction> {
+    program: Vec<I>,
+    pub state: I::State,
+    ip: usize,
+    cycle: usize,
+    // cycles spent so far on the instruction at `ip`
+    busy: usize,
+    halted: bool,
+    paused: bool,
+    cycle_breaks: HashSet<usize>,
+    trace: Option<Vec<String>>,
+}
+
+impl<I> Machine<I>
+where
+    I: Instruction + Display,
+    I::State: Debug,
+{
+    pub fn new(program: Vec<I>, state: I::State) -> Self {
+        Self {
+            program,
+            state,
+            ip: 0,
+            cycle: 0,
+            busy: 0,
+            halted: false,
+            paused: false,
+            cycle_breaks: HashSet::new(),
+            trace: None,
+        }
+    }
+
+    /// Keeps a line per cycle with the instruction running and the state
+    /// during that cycle, see [`Machine::trace`].
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn break_at_cycle(&mut self, cycle: usize) {
+        self.cycle_breaks.insert(cycle);
+    }
+
+    pub fn trace(&self) -> &[String] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Runs until the program halts or hits a breakpoint, showing every cycle
+    /// to `device`.
+    pub fn run(&mut self, device: &mut impl Device<I::State>) -> Stop {
+        loop {
+            if let Some(stop) = self.step(device) {
+                return stop;
+            }
+        }
+    }
+
+    /// Runs a single cycle, returning why it stopped if it hit a breakpoint
+    /// or there was nothing left to run.
+    pub fn step(&mut self, device: &mut impl Device<I::State>) -> Option<Stop> {
+        if self.halted {
+            return Some(Stop::Halted);
+        }
+        let Some(instruction) = self.program.get(self.ip) else {
+            self.halted = true;
+            return Some(Stop::Halted);
+        };
+
+        // a cycle breakpoint leaves the cycle started but not finished
+        if !self.paused {
+            self.cycle += 1;
+            self.busy += 1;
+            device.tick(self.cycle, &self.state);
+            if let Some(trace) = &mut self.trace {
+                trace.push(format!(
+                    "{:>5} {:>4}  {:<16} {:?}",
+                    self.cycle,
+                    self.ip,
+                    instruction.to_string(),
+                    self.state
+                ));
+            }
+            if self.cycle_breaks.contains(&self.cycle) {
+                self.paused = true;
+                return Some(Stop::Cycle(self.cycle));
+            }
+        }
+        self.paused = false;
+
+        if self.busy >= instruction.cycles() {
+            self.busy = 0;
+            instruction.execute(&mut self.state);
+            self.ip += 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // adds `a` into `b`, then counts `a` down
+    #[derive(Debug)]
+    enum Op {
+        Add,
+        Dec,
+    }
+
+    #[derive(Debug, Default)]
+    struct Regs {
+        a: i64,
+        b: i64,
+    }
+
+    impl Instruction for Op {
+        type State = Regs;
+
+        fn cycles(&self) -> usize {
+            match self {
+                Op::Add => 2,
+                Op::Dec => 1,
+            }
+        }
+
+        fn execute(&self, regs: &mut Regs) {
+            match self {
+                Op::Add => regs.b += regs.a,
+                Op::Dec => regs.a -= 1,
+            }
+        }
+    }
+
+    impl Display for Op {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Op::Add => write!(f, "add b a"),
+                Op::Dec => write!(f, "dec a"),
+            }
+        }
+    }
+
+    struct Probe(Vec<(usize, i64)>);
+
+    impl Device<Regs> for Probe {
+        fn tick(&mut self, cycle: usize, regs: &Regs) {
+            self.0.push((cycle, regs.b));
+        }
+    }
+
+    fn machine() -> Machine<Op> {
+        let program = [Op::Add, Op::Dec, Op::Add, Op::Dec, Op::Add, Op::Dec];
+        Machine::new(program.into(), Regs { a: 3, b: 0 })
+    }
+
+    #[test]
+    fn test_run() {
+        let mut cpu = machine().with_trace();
+        let mut probe = Probe(Vec::new());
+        assert_eq!(cpu.run(&mut probe), Stop::Halted);
+        assert_eq!((cpu.state.a, cpu.state.b), (0, 6));
+        assert_eq!(cpu.run(&mut probe), Stop::Halted);
+
+        // the add only lands once both its cycles are over
+        assert_eq!(&probe.0[..4], [(1, 0), (2, 0), (3, 3), (4, 3)]);
+        assert_eq!(probe.0.len(), 9);
+        assert_eq!(cpu.trace().len(), 9);
+        assert_eq!(
+            cpu.trace()[2],
+            "    3    1  dec a            Regs { a: 3, b: 3 }"
+        );
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut cpu = machine();
+        cpu.break_at_cycle(2);
+        cpu.break_at_cycle(5);
+
+        // stopped during the add's last cycle, so it hasn't landed yet
+        assert_eq!(cpu.run(&mut ()), Stop::Cycle(2));
+        assert_eq!(cpu.state.b, 0);
+        // part way through the second add, and picking up where it left off
+        assert_eq!(cpu.run(&mut ()), Stop::Cycle(5));
+        assert_eq!((cpu.state.a, cpu.state.b), (2, 3));
+        assert_eq!(cpu.run(&mut ()), Stop::Halted);
+        assert_eq!(cpu.state.b, 6);
+    }
+}
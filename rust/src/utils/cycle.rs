@@ -0,0 +1,111 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    ops::{Add, Mul, Sub},
+};
+
+/// A simulation that settles into a loop: after `start` steps, the state
+/// repeats every `period` steps. Keeps the value measured after each step up
+/// to the end of the first loop, so later steps can be worked out without
+/// simulating them.
+#[derive(Debug, Clone)]
+pub struct Cycle<V> {
+    pub start: usize,
+    pub period: usize,
+    values: Vec<V>,
+}
+
+/// Steps `state` until the key of a state repeats one seen before, measuring
+/// `value` after every step (and before the first). Keys are remembered in a
+/// hash map, so `key` only needs to capture what decides the future of the
+/// simulation. Never returns if the simulation doesn't loop.
+pub fn find_cycle<S, K, V>(
+    mut state: S,
+    mut key: impl FnMut(&S) -> K,
+    mut value: impl FnMut(&S) -> V,
+    mut step: impl FnMut(&mut S),
+) -> Cycle<V>
+where
+    K: Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    let mut values = Vec::new();
+    loop {
+        values.push(value(&state));
+        let steps = values.len() - 1;
+        if let Some(start) = seen.insert(key(&state), steps) {
+            return Cycle {
+                start,
+                period: steps - start,
+                values,
+            };
+        }
+        step(&mut state);
+    }
+}
+
+impl<V> Cycle<V> {
+    /// Where step `n` falls within the first pass through the loop.
+    fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+
+    /// The value after `n` steps, for values that grow by the same amount
+    /// every time around the loop (e.g. a height or a running total).
+    pub fn extrapolate(&self, n: usize) -> V
+    where
+        V: Copy + Add<Output = V> + Sub<Output = V> + Mul<Output = V> + TryFrom<usize>,
+    {
+        if n < self.values.len() {
+            return self.values[n];
+        }
+
+        let loops = V::try_from((n - self.start) / self.period)
+            .unwrap_or_else(|_| panic!("too many loops to count"));
+        let growth = self.values[self.start + self.period] - self.values[self.start];
+        self.values[self.equivalent_step(n)] + growth * loops
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_cycle() {
+        // 3, 10, 5, 16, 8, 4, 2, 1, 4, 2, 1, ...
+        let collatz = |n: &mut u64| {
+            *n = if n.is_multiple_of(2) {
+                *n / 2
+            } else {
+                3 * *n + 1
+            }
+        };
+        let cycle = find_cycle(3u64, |n| *n, |n| *n, collatz);
+        assert_eq!((cycle.start, cycle.period), (5, 3));
+        assert_eq!(cycle.extrapolate(1), 10);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // a total that gains 1, 2, 3, 1, 2, 3, ... after a run-in of 10
+        let cycle = find_cycle(
+            (0usize, 10u64),
+            |(i, _)| (*i > 0, *i % 3),
+            |(_, total)| *total,
+            |(i, total)| {
+                *total += if *i == 0 { 10 } else { (*i % 3) as u64 + 1 };
+                *i += 1;
+            },
+        );
+        assert_eq!((cycle.start, cycle.period), (1, 3));
+        assert_eq!(cycle.extrapolate(2), 22);
+
+        let brute = (0..1000).fold(10, |t, i| t + if i == 0 { 10 } else { i % 3 + 1 });
+        assert_eq!(cycle.extrapolate(1000), brute);
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+/// A `(row, col)` position in a [`Grid`].
+pub type Pos = (usize, usize);
+
+/// `(row, col)` offsets for all eight neighbours, clockwise from up.
+pub const DIRS_8: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// A dense 2d grid stored row-major in a single `Vec`, indexed by `(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self {
+            cells: Vec::new(),
+            width: 0,
+            height: 0,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// # Panics
+    ///
+    /// Panics if `cells` doesn't hold exactly `width * height` values.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "grid size mismatch");
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Builds a grid from text, one row per line, converting each character
+    /// with `f`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lines aren't all the same length.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        Self::parse_with_pos(input, |_, c| f(c))
+    }
+
+    /// Like [`Grid::parse`], but also hands `f` the position of each character.
+    pub fn parse_with_pos(input: &str, mut f: impl FnMut(Pos, char) -> T) -> Self {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+        for (row, line) in input.lines().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars().enumerate().map(|(col, c)| f((row, col), c)));
+            let len = cells.len() - before;
+            let expected = *width.get_or_insert(len);
+            assert_eq!(
+                len, expected,
+                "line {row} is a different length to the first"
+            );
+            height += 1;
+        }
+        let width = width.unwrap_or(0);
+        Self::new(width, height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, row: isize, col: isize) -> bool {
+        row >= 0 && col >= 0 && (row as usize) < self.height && (col as usize) < self.width
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.height && col < self.width {
+            Some(&self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    /// Bounds-checked access with signed coordinates, handy for `pos + offset`.
+    pub fn get_signed(&self, row: isize, col: isize) -> Option<&T> {
+        if self.in_bounds(row, col) {
+            self.get(row as usize, col as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Access that wraps around both edges, as if the grid tiled the plane.
+    /// Only an empty grid has nothing to wrap onto.
+    pub fn get_wrapping(&self, row: isize, col: isize) -> Option<&T> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let row = row.rem_euclid(self.height as isize) as usize;
+        let col = col.rem_euclid(self.width as isize) as usize;
+        self.get(row, col)
+    }
+
+    /// Steps from `pos` by `offset`, returning the new position if it's still
+    /// on the grid.
+    pub fn offset(&self, (row, col): Pos, (dr, dc): (isize, isize)) -> Option<Pos> {
+        let (row, col) = (row as isize + dr, col as isize + dc);
+        self.in_bounds(row, col)
+            .then_some((row as usize, col as usize))
+    }
+
+    /// Every cell alongside its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ((i / self.width, i % self.width), v))
+    }
+
+    /// The in-bounds orthogonal and diagonal neighbours of `pos`.
+    pub fn neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS_8.iter().filter_map(move |d| self.offset(pos, *d))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    /// Walks away from `pos` in steps of `dir` until leaving the grid. The
+    /// starting cell itself isn't included.
+    pub fn ray(&self, pos: Pos, dir: (isize, isize)) -> impl Iterator<Item = (Pos, &T)> {
+        std::iter::successors(self.offset(pos, dir), move |p| self.offset(*p, dir))
+            .map(|p| (p, &self[p]))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): Pos) -> &Self::Output {
+        assert!(
+            row < self.height && col < self.width,
+            "({row}, {col}) out of bounds"
+        );
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (row, col): Pos) -> &mut Self::Output {
+        assert!(
+            row < self.height && col < self.width,
+            "({row}, {col}) out of bounds"
+        );
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_grid() -> Grid<char> {
+        Grid::parse("abc\ndef", |c| c)
+    }
+
+    #[test]
+    fn test_parse_and_access() {
+        let grid = get_example_grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_wrapping(-1, 4), Some(&'e'));
+        assert_eq!(Grid::<char>::default().get_wrapping(0, 0), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+    }
+
+    #[test]
+    #[should_panic(expected = "line 1 is a different length")]
+    fn test_parse_ragged() {
+        // 6 cells would fit a 2 wide grid if only the last line counted
+        Grid::parse("abc\nd\nef", |c| c);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = get_example_grid();
+        assert_eq!(grid.neighbours_8((0, 1)).count(), 5);
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_rows_cols_and_rays() {
+        let grid = get_example_grid();
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.col(1).collect::<String>(), "be");
+        assert_eq!(
+            grid.ray((0, 0), (0, 1)).map(|(_, c)| c).collect::<String>(),
+            "bc"
+        );
+        assert_eq!(
+            grid.ray((0, 0), (1, 1)).collect::<Vec<_>>(),
+            vec![((1, 1), &'e')]
+        );
+        assert_eq!(grid.ray((1, 2), (0, 1)).count(), 0);
+    }
+}
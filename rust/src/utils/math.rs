@@ -0,0 +1,568 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+    str::FromStr,
+};
+
+use super::point::Num;
+
+/// Integer types with division, for the number theory helpers.
+pub trait Integer: Num + Ord + Div<Output = Self> + Rem<Output = Self> {}
+
+impl<T: Num + Ord + Div<Output = T> + Rem<Output = T>> Integer for T {}
+
+/// The greatest common divisor, always non-negative.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    if a < T::ZERO {
+        T::ZERO - a
+    } else {
+        a
+    }
+}
+
+/// The least common multiple, or zero if either value is zero.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    let l = a / gcd(a, b) * b;
+    if l < T::ZERO {
+        T::ZERO - l
+    } else {
+        l
+    }
+}
+
+/// The least common multiple of every value, or `None` if there are none.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().reduce(lcm)
+}
+
+/// `base` to the power of `exp`, modulo `modulus`.
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// An exact fraction, always kept in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// # Panics
+    ///
+    /// Panics if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "zero denominator");
+        let g = gcd(num, den);
+        let sign = den.signum();
+        Self {
+            num: sign * num / g,
+            den: sign * den / g,
+        }
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    pub fn recip(self) -> Self {
+        Self::new(self.den, self.num)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Self { num: value, den: 1 }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::from(value as i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        let den = lcm(self.den, rhs.den);
+        Self::new(self.num * (den / self.den) + rhs.num * (den / rhs.den), den)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        // cancel across first to keep the intermediate values small
+        let a = gcd(self.num, rhs.den).max(1);
+        let b = gcd(rhs.num, self.den).max(1);
+        Self::new(
+            (self.num / a) * (rhs.num / b),
+            (self.den / b) * (rhs.den / a),
+        )
+    }
+}
+
+/// # Panics
+///
+/// Panics when dividing by zero.
+impl Div for Rational {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.recip()
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num * other.den).cmp(&(other.num * self.den))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// An arbitrary precision signed integer. Division truncates towards zero,
+/// like Rust's own integers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    // little endian base 2^32 digits, with no trailing zeros
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.magnitude.clone())
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = BigInt::from(1i64);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// The quotient and remainder, truncating towards zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics when dividing by zero.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        assert!(!rhs.is_zero(), "attempt to divide by zero");
+        let (q, r) = div_rem_mag(&self.magnitude, &rhs.magnitude);
+        (
+            Self::from_parts(self.negative != rhs.negative, q),
+            Self::from_parts(self.negative, r),
+        )
+    }
+
+    /// The value as an `i128`, if it fits.
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.magnitude.len() > 4 {
+            return None;
+        }
+        let mag = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0u128, |acc, d| (acc << 32) | *d as u128);
+        if self.negative {
+            0i128.checked_sub_unsigned(mag)
+        } else {
+            i128::try_from(mag).ok()
+        }
+    }
+
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        Self {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, d) in long.iter().enumerate() {
+        let sum = *d as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        out.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        out.push(carry as u32);
+    }
+    out
+}
+
+/// `a - b`, where `a` is at least as big as `b`.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, d) in a.iter().enumerate() {
+        let mut diff = *d as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = (diff < 0) as i64;
+        if diff < 0 {
+            diff += 1 << 32;
+        }
+        out.push(diff as u32);
+    }
+    out
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let cur = out[i + j] as u64 + *x as u64 * *y as u64 + carry;
+            out[i + j] = cur as u32;
+            carry = cur >> 32;
+        }
+        out[i + b.len()] = carry as u32;
+    }
+    out
+}
+
+/// Schoolbook binary long division, slow but plenty for puzzle sized numbers.
+fn div_rem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_mag(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+
+    if let [d] = b {
+        let mut q = vec![0u32; a.len()];
+        let mut r = 0u64;
+        for i in (0..a.len()).rev() {
+            let cur = (r << 32) | a[i] as u64;
+            q[i] = (cur / *d as u64) as u32;
+            r = cur % *d as u64;
+        }
+        return (q, vec![r as u32]);
+    }
+
+    let mut q = vec![0u32; a.len()];
+    let mut r: Vec<u32> = Vec::new();
+    for i in (0..a.len() * 32).rev() {
+        // r = r << 1 | bit i of a
+        let mut carry = (a[i / 32] >> (i % 32)) & 1;
+        for d in r.iter_mut() {
+            let next = *d >> 31;
+            *d = (*d << 1) | carry;
+            carry = next;
+        }
+        if carry > 0 {
+            r.push(carry);
+        }
+
+        if cmp_mag(&r, b) != Ordering::Less {
+            r = sub_mag(&r, b);
+            while r.last() == Some(&0) {
+                r.pop();
+            }
+            q[i / 32] |= 1 << (i % 32);
+        }
+    }
+    (q, r)
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        let mut mag = value.unsigned_abs();
+        let mut magnitude = Vec::new();
+        while mag > 0 {
+            magnitude.push(mag as u32);
+            mag >>= 32;
+        }
+        Self::from_parts(value < 0, magnitude)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        Self::from(value as i128)
+    }
+}
+
+impl<'a> Add<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: &'a BigInt) -> Self::Output {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, add_mag(&self.magnitude, &rhs.magnitude));
+        }
+        match cmp_mag(&self.magnitude, &rhs.magnitude) {
+            Ordering::Less => {
+                BigInt::from_parts(rhs.negative, sub_mag(&rhs.magnitude, &self.magnitude))
+            }
+            _ => BigInt::from_parts(self.negative, sub_mag(&self.magnitude, &rhs.magnitude)),
+        }
+    }
+}
+
+impl<'a> Sub<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: &'a BigInt) -> Self::Output {
+        self + &-rhs
+    }
+}
+
+impl<'a> Mul<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: &'a BigInt) -> Self::Output {
+        BigInt::from_parts(
+            self.negative != rhs.negative,
+            mul_mag(&self.magnitude, &rhs.magnitude),
+        )
+    }
+}
+
+impl<'a> Div<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+    fn div(self, rhs: &'a BigInt) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl<'a> Rem<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+    fn rem(self, rhs: &'a BigInt) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> Self::Output {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+macro_rules! forward_owned_ops {
+    ($($trait:ident $method:ident),*) => {
+        $(impl $trait for BigInt {
+            type Output = BigInt;
+            fn $method(self, rhs: BigInt) -> Self::Output {
+                (&self).$method(&rhs)
+            }
+        })*
+    };
+}
+
+forward_owned_ops!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl Neg for BigInt {
+    type Output = BigInt;
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.magnitude, &other.magnitude),
+            (true, true) => cmp_mag(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // peel off nine decimal digits at a time
+        let mut chunks = Vec::new();
+        let mut mag = self.magnitude.clone();
+        while !mag.is_empty() {
+            let (q, r) = div_rem_mag(&mag, &[DECIMAL_CHUNK]);
+            chunks.push(r.first().copied().unwrap_or(0));
+            mag = BigInt::from_parts(false, q).magnitude;
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", chunks.last().unwrap())?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{chunk:09}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("invalid integer {s:?}"));
+        }
+
+        let mut magnitude = Vec::new();
+        for chunk in digits.as_bytes().chunks(9) {
+            let scale = 10u32.pow(chunk.len() as u32);
+            let value = chunk.iter().fold(0, |acc, b| acc * 10 + (b - b'0') as u32);
+            magnitude = add_mag(&mul_mag(&magnitude, &[scale]), &[value]);
+        }
+        Ok(Self::from_parts(negative, magnitude))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0u32, 5), 5);
+        assert_eq!(lcm(4usize, 6), 12);
+        assert_eq!(lcm_all([23u64, 19, 13, 17]), Some(96577));
+        assert_eq!(lcm_all(Vec::<u64>::new()), None);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_pow(252533, 20000, 33554393), 33458511);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(1, 2);
+        let third = Rational::new(-2, -6);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert_eq!((half / third).to_string(), "3/2");
+        assert!(Rational::new(4, -2).is_integer());
+        assert!(third < half);
+    }
+
+    #[test]
+    fn test_bigint() {
+        let factorial = (1..=30i64).fold(BigInt::from(1i64), |acc, n| acc * BigInt::from(n));
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+        assert_eq!(
+            "265252859812191058636308480000000".parse::<BigInt>(),
+            Ok(factorial.clone())
+        );
+
+        let two_100 = BigInt::from(2i64).pow(100);
+        assert_eq!(two_100.to_string(), "1267650600228229401496703205376");
+        assert_eq!((&two_100 - &two_100).to_string(), "0");
+        assert_eq!(
+            (&factorial / &two_100).to_string(),
+            (265252859812191058636308480000000u128 / (1u128 << 100)).to_string()
+        );
+
+        let a: BigInt = "-123456789012345678901234567890".parse().unwrap();
+        let b: BigInt = "987654321".parse().unwrap();
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(q.to_string(), "-124999998873437499901");
+        assert_eq!(r.to_string(), "-574845669");
+        assert_eq!(&(&q * &b) + &r, a);
+        assert!(a < b && -&a > b);
+        assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+        assert_eq!(BigInt::from(i128::MAX).to_i128(), Some(i128::MAX));
+        assert_eq!((&two_100 * &two_100).to_i128(), None);
+    }
+}
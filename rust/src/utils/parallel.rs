@@ -0,0 +1,161 @@
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread::{self, available_parallelism},
+};
+
+// 0 means one thread per core
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets how many threads the `par_*` helpers use, with 0 meaning one per
+/// core.
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+/// Runs `work` on `workers` scoped threads and collects what each returns.
+/// A panic on any worker is passed on to the caller.
+fn run_workers<R: Send>(workers: usize, work: impl Fn() -> R + Sync) -> Vec<R> {
+    thread::scope(|s| {
+        let handles = (0..workers).map(|_| s.spawn(&work)).collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+/// How many indices a worker claims at once when searching a range, so the
+/// shared counter isn't hammered for cheap checks.
+fn batch_size(range: &Range<usize>, workers: usize) -> usize {
+    (range.len() / (workers * 64)).clamp(1, 4096)
+}
+
+/// Maps every item on a worker thread, returning the results in order.
+/// Workers take one item at a time, so it copes with items that vary a lot in
+/// how long they take.
+pub fn par_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = threads().min(items.len());
+    if workers <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results = run_workers(workers, || {
+        let mut done = Vec::new();
+        loop {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            let Some(item) = items.get(i) else {
+                break done;
+            };
+            done.push((i, f(item)));
+        }
+    })
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+
+    results.sort_unstable_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+/// Checks indices from `range` across the workers until any of them gives a
+/// result, then stops them all. Which result comes back is up to whichever
+/// worker gets there first.
+pub fn par_find_any<R: Send>(
+    range: Range<usize>,
+    f: impl Fn(usize) -> Option<R> + Sync,
+) -> Option<R> {
+    let workers = threads();
+    let batch = batch_size(&range, workers);
+    let next = AtomicUsize::new(range.start);
+    let found = AtomicBool::new(false);
+
+    run_workers(workers, || {
+        while !found.load(Ordering::Relaxed) {
+            let start = next.fetch_add(batch, Ordering::Relaxed);
+            if start >= range.end {
+                break;
+            }
+            for i in start..range.end.min(start.saturating_add(batch)) {
+                if let Some(result) = f(i) {
+                    found.store(true, Ordering::Relaxed);
+                    return Some(result);
+                }
+                if found.load(Ordering::Relaxed) {
+                    break;
+                }
+            }
+        }
+        None
+    })
+    .into_iter()
+    .flatten()
+    .next()
+}
+
+/// Like [`par_find_any`], but gives the result for the lowest index that has
+/// one. Batches are handed out in order and workers stop once their next
+/// batch starts beyond the best hit so far, so everything below the answer is
+/// always checked.
+pub fn par_find_first<R: Send>(
+    range: Range<usize>,
+    f: impl Fn(usize) -> Option<R> + Sync,
+) -> Option<R> {
+    let workers = threads();
+    let batch = batch_size(&range, workers);
+    let next = AtomicUsize::new(range.start);
+    let best = AtomicUsize::new(usize::MAX);
+
+    run_workers(workers, || loop {
+        let start = next.fetch_add(batch, Ordering::Relaxed);
+        if start >= range.end || start >= best.load(Ordering::Relaxed) {
+            break None;
+        }
+        let end = range.end.min(start.saturating_add(batch));
+        if let Some((i, result)) = (start..end).find_map(|i| f(i).map(|r| (i, r))) {
+            best.fetch_min(i, Ordering::Relaxed);
+            // this worker's later batches all start past it
+            break Some((i, result));
+        }
+    })
+    .into_iter()
+    .flatten()
+    .min_by_key(|(i, _)| *i)
+    .map(|(_, r)| r)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_par_map() {
+        let items = (0..1000u64).collect::<Vec<_>>();
+        let borrowed = &items;
+        let squares = par_map(&items, |i| i * i + borrowed[0]);
+        assert_eq!(squares, items.iter().map(|i| i * i).collect::<Vec<_>>());
+
+        assert!(par_map(&[] as &[u64], |i| *i).is_empty());
+    }
+
+    #[test]
+    fn test_par_find() {
+        let found = par_find_any(0..1_000_000, |i| (i % 1234 == 1233).then_some(i));
+        assert_eq!(found.map(|i| i % 1234), Some(1233));
+        assert_eq!(par_find_any(0..1000, |_| None::<()>), None);
+
+        // hits from many batches, only the lowest should win
+        let first = par_find_first(10..usize::MAX, |i| (i % 7 == 0).then_some(i));
+        assert_eq!(first, Some(14));
+        let first = par_find_first(0..100_000, |i| (i > 54_321).then_some(i * 2));
+        assert_eq!(first, Some(108_644));
+    }
+}
@@ -0,0 +1,318 @@
+// helpers for picking puzzle input apart without long chains of
+// split_once(..).unwrap(), reporting where things went wrong instead of
+// panicking
+
+use std::{fmt::Display, str::FromStr};
+
+/// A parse failure, located by 1-based line and column within the text that
+/// was being parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error at byte `offset` into `input`.
+    pub fn at(input: &str, offset: usize, message: impl Into<String>) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// Moves an error found in `part` to where `part` sits within `whole`,
+    /// where `part` is a slice of `whole` starting on a line of its own.
+    fn relocate(mut self, whole: &str, part: &str) -> Self {
+        let offset = part.as_ptr() as usize - whole.as_ptr() as usize;
+        self.line += whole[..offset].matches('\n').count();
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The pieces of some input matched by the `{}` placeholders of a pattern.
+#[derive(Debug, Clone)]
+pub struct Captures<'a> {
+    input: &'a str,
+    // byte offset into the input and the captured text
+    captures: Vec<(usize, &'a str)>,
+}
+
+impl<'a> Captures<'a> {
+    fn len(&self) -> usize {
+        self.captures.len()
+    }
+
+    pub fn str(&self, i: usize) -> &'a str {
+        self.captures[i].1
+    }
+
+    pub fn get<T: FromStr>(&self, i: usize) -> Result<T, ParseError> {
+        let (offset, text) = self.captures[i];
+        text.parse()
+            .map_err(|_| ParseError::at(self.input, offset, format!("invalid value {text:?}")))
+    }
+
+    pub fn into_tuple<T: FromCaptures>(self) -> Result<T, ParseError> {
+        if self.len() != T::COUNT {
+            return Err(ParseError::at(
+                self.input,
+                0,
+                format!("expected {} values, found {}", T::COUNT, self.len()),
+            ));
+        }
+        T::from_captures(&self)
+    }
+}
+
+/// Tuples that can be filled in from [`Captures`], one value per placeholder.
+pub trait FromCaptures: Sized {
+    const COUNT: usize;
+    fn from_captures(captures: &Captures) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_from_captures {
+    ($count:expr; $($t:ident $i:tt),*) => {
+        impl<$($t: FromStr),*> FromCaptures for ($($t,)*) {
+            const COUNT: usize = $count;
+            fn from_captures(captures: &Captures) -> Result<Self, ParseError> {
+                Ok(($(captures.get::<$t>($i)?,)*))
+            }
+        }
+    };
+}
+
+impl_from_captures!(1; A 0);
+impl_from_captures!(2; A 0, B 1);
+impl_from_captures!(3; A 0, B 1, C 2);
+impl_from_captures!(4; A 0, B 1, C 2, D 3);
+impl_from_captures!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_captures!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_captures!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// Matches `literal` at byte `at` of `input`, where any run of whitespace in
+/// the literal matches any (non-empty) run of whitespace in the input.
+/// Returns where the match ends.
+fn match_literal(literal: &str, input: &str, mut at: usize) -> Result<usize, usize> {
+    let mut pattern = literal.chars().peekable();
+    while let Some(c) = pattern.next() {
+        let rest = &input[at..];
+        if c.is_whitespace() {
+            while pattern.next_if(|c| c.is_whitespace()).is_some() {}
+            let spaces = rest.len() - rest.trim_start().len();
+            if spaces == 0 {
+                return Err(at);
+            }
+            at += spaces;
+        } else if rest.starts_with(c) {
+            at += c.len_utf8();
+        } else {
+            return Err(at);
+        }
+    }
+    Ok(at)
+}
+
+/// Matches `input` against `pattern`, capturing the text at each `{}`. Each
+/// capture runs up to the first place the literal text that follows it
+/// matches, and is trimmed of surrounding whitespace.
+pub fn pattern<'a>(pattern: &str, input: &'a str) -> Result<Captures<'a>, ParseError> {
+    let mut literals = pattern.split("{}");
+    let first = literals.next().unwrap_or_default();
+    let mut at = match_literal(first, input, 0)
+        .map_err(|at| ParseError::at(input, at, format!("expected {first:?}")))?;
+
+    let mut captures = Vec::new();
+    for literal in literals {
+        let start = at;
+        let (end, after) = if literal.is_empty() {
+            (input.len(), input.len())
+        } else {
+            input[start..]
+                .char_indices()
+                .map(|(i, _)| start + i)
+                .chain([input.len()])
+                .find_map(|i| {
+                    match_literal(literal, input, i)
+                        .ok()
+                        .map(|after| (i, after))
+                })
+                .ok_or_else(|| ParseError::at(input, start, format!("expected {literal:?}")))?
+        };
+
+        let text = &input[start..end];
+        let trimmed = text.trim_start();
+        captures.push((start + text.len() - trimmed.len(), trimmed.trim_end()));
+        at = after;
+    }
+
+    if at < input.len() && !input[at..].trim().is_empty() {
+        return Err(ParseError::at(input, at, "unexpected trailing input"));
+    }
+
+    Ok(Captures { input, captures })
+}
+
+/// Matches a line (or more) of input against a pattern, parsing each `{}`
+/// into the matching element of the tuple being assigned to:
+///
+/// ```ignore
+/// let (x, y): (i64, i64) = parse!("x={}, y={}", line)?;
+/// ```
+macro_rules! parse {
+    ($pattern:expr, $input:expr) => {
+        $crate::utils::parse::pattern($pattern, $input).and_then(|c| c.into_tuple())
+    };
+}
+
+pub(crate) use parse;
+
+/// Every integer in `s`, in order. A `-` directly before a number makes it
+/// negative unless it follows another digit, so `1-3` is read as `1` and `3`.
+/// Numbers too big for `T` are skipped.
+pub fn ints<T: FromStr>(s: &str) -> impl Iterator<Item = T> + '_ {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let start = i;
+            let negative = bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                && (i == 0 || !bytes[i - 1].is_ascii_digit());
+            if negative {
+                i += 1;
+            }
+            if !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            if let Ok(n) = s[start..i].parse() {
+                return Some(n);
+            }
+        }
+        None
+    })
+}
+
+/// Splits input into blocks separated by blank lines.
+pub fn paragraphs(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input.trim_start_matches(['\n', '\r']);
+    std::iter::from_fn(move || {
+        if rest.trim().is_empty() {
+            return None;
+        }
+        let (block, next) = match rest.find("\n\n").or_else(|| rest.find("\n\r\n")) {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, ""),
+        };
+        rest = next.trim_start_matches(['\n', '\r']);
+        Some(block.trim_end())
+    })
+}
+
+/// Parses each line with `f`, reporting errors against the whole input.
+pub fn lines<T>(
+    input: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .map(|line| f(line).map_err(|e| e.relocate(input, line)))
+        .collect()
+}
+
+/// Parses each blank-line separated block with `f`, reporting errors against
+/// the whole input.
+pub fn blocks<T>(
+    input: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    paragraphs(input)
+        .map(|block| f(block).map_err(|e| e.relocate(input, block)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_macro() {
+        let line = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        let parsed: Result<(i64, i64, i64, i64), ParseError> = parse!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            line
+        );
+        assert_eq!(parsed, Ok((2, -18, -2, 15)));
+
+        // whitespace in the pattern matches any whitespace, including newlines
+        let (name, items): (String, String) = parse!(
+            "Monkey {}: Starting items: {}",
+            "Monkey 0:\n  Starting items: 79, 98"
+        )
+        .unwrap();
+        assert_eq!((name.as_str(), items.as_str()), ("0", "79, 98"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse!("x={}, y={}", "x=1, z=2")
+            .map(|(_, _): (i32, i32)| ())
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(err.to_string(), "1:3: expected \", y=\"");
+
+        let err = parse!("x={}, y={}", "x=1, y=two")
+            .map(|(_, _): (i32, i32)| ())
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (1, 8));
+
+        let err = parse!("x={}, y={}", "x=1, y=2")
+            .map(|(_,): (i32,)| ())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "1:1: expected 1 values, found 2");
+
+        let err = lines("1\n2\nthree", |l| parse!("{}", l).map(|(n,): (u8,)| n)).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+
+    #[test]
+    fn test_ints() {
+        let found = ints::<i32>("Sensor at x=-2, y=15: 2-4,6-8 and 1 - -3").collect::<Vec<_>>();
+        assert_eq!(found, vec![-2, 15, 2, 4, 6, 8, 1, -3]);
+        assert_eq!(ints::<u8>("300 5").collect::<Vec<_>>(), vec![5]);
+        assert_eq!(ints::<i32>("no numbers - here").count(), 0);
+    }
+
+    #[test]
+    fn test_paragraphs_and_blocks() {
+        let input = "a\nb\n\nc\n\n\nd\n";
+        assert_eq!(
+            paragraphs(input).collect::<Vec<_>>(),
+            vec!["a\nb", "c", "d"]
+        );
+
+        let err = blocks(input, |b| parse!("{}", b).map(|(c,): (char,)| c)).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        assert_eq!(
+            blocks("a\n\nb", |b| parse!("{}", b).map(|(c,): (char,)| c)),
+            Ok(vec!['a', 'b'])
+        );
+    }
+}
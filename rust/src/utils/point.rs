@@ -0,0 +1,451 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// The integer types points can be built from.
+pub trait Num:
+    Copy
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs_diff_of(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+
+    fn max_of(self, other: Self) -> Self {
+        if self > other {
+            self
+        } else {
+            other
+        }
+    }
+}
+
+/// Integer types that can point in a negative direction.
+pub trait Signed: Num + Neg<Output = Self> {
+    fn signum(self) -> Self;
+}
+
+macro_rules! impl_num {
+    ($($t:ty),*) => {
+        $(impl Num for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(impl Signed for $t {
+
+            fn signum(self) -> Self {
+                <$t>::signum(self)
+            }
+        })*
+    };
+}
+
+impl_num!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+/// A 2d point or vector. Where a puzzle has an up and down, `y` grows
+/// downwards, matching the order rows are read in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Num> Point2<T> {
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.abs_diff_of(other.x) + self.y.abs_diff_of(other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x
+            .abs_diff_of(other.x)
+            .max_of(self.y.abs_diff_of(other.y))
+    }
+
+    /// Whether `other` is this point or one of its eight neighbours.
+    pub fn is_touching(&self, other: &Self) -> bool {
+        self.chebyshev(other) <= T::ONE
+    }
+}
+
+impl<T: Signed> Point2<T> {
+    /// Each component clamped to -1, 0 or 1, i.e. the single step (including
+    /// diagonals) that heads towards this vector.
+    pub fn signum(&self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn neighbours_4(&self) -> [Self; 4] {
+        Direction::ALL.map(|d| *self + d.offset())
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// Parses `x,y` as well as the labelled `x=1, y=2` form.
+impl<T: FromStr> FromStr for Point2<T> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y] = parse_components(s, ["x", "y"])?;
+        Ok(Self::new(x, y))
+    }
+}
+
+/// A 3d point or vector.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Signed> Point3<T> {
+    /// The six points sharing a face with this one.
+    pub fn neighbours_6(&self) -> [Self; 6] {
+        let (o, i) = (T::ZERO, T::ONE);
+        [
+            Self::new(i, o, o),
+            Self::new(-i, o, o),
+            Self::new(o, i, o),
+            Self::new(o, -i, o),
+            Self::new(o, o, i),
+            Self::new(o, o, -i),
+        ]
+        .map(|d| *self + d)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+/// Parses `x,y,z` as well as the labelled `x=1, y=2, z=3` form.
+impl<T: FromStr> FromStr for Point3<T> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y, z] = parse_components(s, ["x", "y", "z"])?;
+        Ok(Self::new(x, y, z))
+    }
+}
+
+fn parse_components<T: FromStr, const N: usize>(
+    s: &str,
+    labels: [&str; N],
+) -> Result<[T; N], String> {
+    let values = s
+        .split(',')
+        .map(|p| p.trim())
+        .zip(labels.iter().chain(std::iter::repeat(&"")))
+        .map(|(part, label)| {
+            let value = part
+                .strip_prefix(label)
+                .and_then(|p| p.strip_prefix('='))
+                .unwrap_or(part);
+            value
+                .parse()
+                .map_err(|_| format!("invalid {label} component {value:?} in {s:?}"))
+        })
+        .collect::<Result<Vec<T>, String>>()?;
+
+    let found = values.len();
+    values
+        .try_into()
+        .map_err(|_| format!("expected {N} components in {s:?}, found {found}"))
+}
+
+/// One of the four orthogonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All four directions, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn offset<T: Signed>(&self) -> Point2<T> {
+        let (o, i) = (T::ZERO, T::ONE);
+        match self {
+            Direction::Up => Point2::new(o, -i),
+            Direction::Right => Point2::new(i, o),
+            Direction::Down => Point2::new(o, i),
+            Direction::Left => Point2::new(-i, o),
+        }
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = String;
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'U' | 'N' | '^' => Ok(Direction::Up),
+            'R' | 'E' | '>' => Ok(Direction::Right),
+            'D' | 'S' | 'v' => Ok(Direction::Down),
+            'L' | 'W' | '<' => Ok(Direction::Left),
+            _ => Err(format!("UNKNOWN DIRECTION: {value}")),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => c.try_into(),
+            _ => Err(format!("UNKNOWN DIRECTION: {s}")),
+        }
+    }
+}
+
+/// One of the eight compass directions, including diagonals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction8 {
+    pub fn offset<T: Signed>(&self) -> Point2<T> {
+        let (o, i) = (T::ZERO, T::ONE);
+        match self {
+            Direction8::N => Point2::new(o, -i),
+            Direction8::NE => Point2::new(i, -i),
+            Direction8::E => Point2::new(i, o),
+            Direction8::SE => Point2::new(i, i),
+            Direction8::S => Point2::new(o, i),
+            Direction8::SW => Point2::new(-i, i),
+            Direction8::W => Point2::new(-i, o),
+            Direction8::NW => Point2::new(-i, -i),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Up => Direction8::N,
+            Direction::Right => Direction8::E,
+            Direction::Down => Direction8::S,
+            Direction::Left => Direction8::W,
+        }
+    }
+}
+
+/// Parses a single direction like `R` or `S`, or an up/down one followed by
+/// a left/right one for a diagonal, like `UL` or `NE`.
+impl FromStr for Direction8 {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let first = chars.next().map(Direction::try_from);
+        match (first, chars.next().map(Direction::try_from), chars.next()) {
+            (Some(d), None, None) => d.map(Direction8::from),
+            (Some(Ok(vertical)), Some(Ok(horizontal)), None) => match (vertical, horizontal) {
+                (Direction::Up, Direction::Right) => Ok(Direction8::NE),
+                (Direction::Down, Direction::Right) => Ok(Direction8::SE),
+                (Direction::Down, Direction::Left) => Ok(Direction8::SW),
+                (Direction::Up, Direction::Left) => Ok(Direction8::NW),
+                _ => Err(format!("UNKNOWN DIRECTION: {s}")),
+            },
+            _ => Err(format!("UNKNOWN DIRECTION: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic_and_distance() {
+        let a = Point2::new(1i32, -2);
+        let b = Point2::new(-3, 4);
+        assert_eq!(a + b, Point2::new(-2, 2));
+        assert_eq!(a - b, Point2::new(4, -6));
+        assert_eq!(-a * 2, Point2::new(-2, 4));
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(a.chebyshev(&b), 6);
+        assert_eq!((b - a).signum(), Point2::new(-1, 1));
+        assert!(a.is_touching(&Point2::new(2, -1)));
+        assert!(!a.is_touching(&Point2::new(3, -1)));
+
+        let unsigned = Point2::new(3u8, 7);
+        assert_eq!(unsigned.manhattan(&Point2::new(5, 1)), 8);
+
+        let c = Point3::new(1i8, 2, 3);
+        assert_eq!(c.neighbours_6().len(), 6);
+        assert!(c.neighbours_6().iter().all(|n| *n != c));
+    }
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!("498,4".parse(), Ok(Point2::new(498, 4)));
+        assert_eq!("x=-2, y=15".parse(), Ok(Point2::new(-2i64, 15)));
+        assert_eq!("1,2,3".parse(), Ok(Point3::new(1i8, 2, 3)));
+        assert_eq!("x=1, y=-2, z=3".parse(), Ok(Point3::new(1, -2, 3)));
+        assert!("1,2,3".parse::<Point2<i32>>().is_err());
+        assert!("x=a, y=2".parse::<Point2<i32>>().is_err());
+        assert!("300,2".parse::<Point2<u8>>().is_err());
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!("v".parse(), Ok(Direction::Down));
+        assert_eq!(Direction::Up.offset::<i32>(), Point2::new(0, -1));
+        assert_eq!(Direction8::from(Direction::Left), Direction8::W);
+        assert_eq!("NE".parse(), Ok(Direction8::NE));
+        assert_eq!("DL".parse(), Ok(Direction8::SW));
+        assert_eq!("W".parse(), Ok(Direction8::W));
+        assert!("RU".parse::<Direction8>().is_err());
+        assert!("NNE".parse::<Direction8>().is_err());
+
+        let origin = Point2::new(0i32, 0);
+        assert_eq!(
+            origin
+                .neighbours_4()
+                .iter()
+                .map(|n| n.manhattan(&origin))
+                .sum::<i32>(),
+            4
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use std::ops::{Bound, Range, RangeBounds};
+
+use super::point::Num;
+
+/// A set of integers stored as sorted, disjoint ranges. Ranges are merged as
+/// they're inserted, so touching or overlapping ranges always collapse into
+/// one. Anything implementing `RangeBounds` can be passed in, so `a..b` and
+/// `a..=b` both work, but unbounded ranges aren't supported.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    // half-open, sorted and never touching
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+/// Converts any bounded range into a half-open one, or `None` if it's empty.
+fn half_open<T: Num + Ord>(range: impl RangeBounds<T>) -> Option<Range<T>> {
+    let start = match range.start_bound() {
+        Bound::Included(s) => *s,
+        Bound::Excluded(s) => *s + T::ONE,
+        Bound::Unbounded => panic!("RangeSet doesn't support unbounded ranges"),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(e) => *e + T::ONE,
+        Bound::Excluded(e) => *e,
+        Bound::Unbounded => panic!("RangeSet doesn't support unbounded ranges"),
+    };
+    (start < end).then_some(start..end)
+}
+
+impl<T: Num + Ord> RangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        let Some(Range { mut start, mut end }) = half_open(range) else {
+            return;
+        };
+
+        // every stored range in first..last touches or overlaps the new one
+        let first = self.ranges.partition_point(|r| r.end < start);
+        let last = self.ranges.partition_point(|r| r.start <= end);
+        if first < last {
+            start = start.min(self.ranges[first].start);
+            end = end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, std::iter::once(start..end));
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= *value);
+        self.ranges.get(i).is_some_and(|r| r.start <= *value)
+    }
+
+    /// How many values the set covers.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |acc, r| acc + (r.end - r.start))
+    }
+
+    /// The holes between the first and last ranges, half-open and in order.
+    pub fn gaps(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.ranges.windows(2).map(|w| w[0].end..w[1].start)
+    }
+}
+
+impl<T: Num + Ord, R: RangeBounds<T>> FromIterator<R> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn gaps(set: &RangeSet<i32>) -> Vec<Range<i32>> {
+        set.gaps().collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = RangeSet::new();
+        set.insert(10..20);
+        set.insert(30..=39);
+        set.insert(0..5);
+        assert_eq!(gaps(&set), vec![5..10, 20..30]);
+        assert_eq!(set.len(), 25);
+
+        // touching ranges join up, as do ones spanning several others
+        set.insert(5..10);
+        assert_eq!(gaps(&set), vec![20..30]);
+        set.insert(-5..=50);
+        assert_eq!(gaps(&set), vec![]);
+
+        // empty ranges are ignored
+        set.insert(100..100);
+        assert_eq!(set.len(), 56);
+    }
+
+    #[test]
+    fn test_queries() {
+        let set = [0..5, 10..15, 16..21]
+            .into_iter()
+            .collect::<RangeSet<i32>>();
+        assert_eq!(gaps(&set), vec![5..10, 15..16]);
+        assert_eq!(set.len(), 15);
+
+        assert!(set.contains(&4));
+        assert!(!set.contains(&5));
+        assert!(set.contains(&20));
+        assert!(!set.contains(&21));
+    }
+}
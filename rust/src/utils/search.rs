@@ -0,0 +1,245 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Anything that can be used as the cost of an edge: summed along a path and
+/// compared to pick the cheapest. `Default` is taken to be zero.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T>> Cost for T {}
+
+/// What a search learned: the cost to reach every state it settled, how it
+/// got there and, if a goal predicate matched, which state that was.
+#[derive(Debug, Clone)]
+pub struct Search<S, C> {
+    distances: HashMap<S, C>,
+    parents: HashMap<S, S>,
+    goal: Option<S>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Search<S, C> {
+    fn new() -> Self {
+        Self {
+            distances: HashMap::new(),
+            parents: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    /// The states from a start up to and including the goal.
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        self.goal.as_ref().and_then(|g| self.path_to(g))
+    }
+
+    /// The states from a start up to and including `state`, if it was reached.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn into_distances(self) -> HashMap<S, C> {
+        self.distances
+    }
+}
+
+/// Breadth first search where every step costs one. Stops as soon as a state
+/// matching `is_goal` is reached, or explores everything reachable if nothing
+/// does.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(current) = queue.pop_front() {
+        if is_goal(&current) {
+            search.goal = Some(current);
+            break;
+        }
+
+        let dist = search.distances[&current] + 1;
+        for next in neighbours(&current) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), dist);
+                search.parents.insert(next.clone(), current.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// The step count to every state reachable from `starts`.
+pub fn bfs_all<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: impl FnMut(&S) -> I,
+) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    bfs(starts, neighbours, |_| false).into_distances()
+}
+
+/// Cheapest-first search guided by `heuristic`, an estimate of the remaining cost from a
+/// state to the goal. The first goal found is only guaranteed to be the
+/// cheapest if the heuristic never overestimates.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search::new();
+    let mut frontier = BinaryHeap::new();
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.distances.insert(start.clone(), C::default());
+            frontier.push(Entry {
+                priority: heuristic(&start),
+                cost: C::default(),
+                state: start,
+            });
+        }
+    }
+
+    while let Some(Entry { cost, state, .. }) = frontier.pop() {
+        // a cheaper route to this state was queued after this one
+        if search.distances.get(&state).is_some_and(|d| *d < cost) {
+            continue;
+        }
+
+        if is_goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            if search.distances.get(&next).is_none_or(|d| next_cost < *d) {
+                search.distances.insert(next.clone(), next_cost);
+                search.parents.insert(next.clone(), state.clone());
+                frontier.push(Entry {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    state: next,
+                });
+            }
+        }
+    }
+
+    search
+}
+
+/// A frontier entry, ordered so the `BinaryHeap` pops the lowest priority.
+struct Entry<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for Entry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S, C: Ord> Eq for Entry<S, C> {}
+
+impl<S, C: Ord> Ord for Entry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.cmp(&other.priority).reverse()
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Entry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // a small directed graph with a tempting but expensive shortcut:
+    // a -1-> b -1-> c -1-> d, and a -5-> d
+    fn weighted(state: &char) -> Vec<(char, u32)> {
+        match state {
+            'a' => vec![('b', 1), ('d', 5)],
+            'b' => vec![('c', 1)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        }
+    }
+
+    fn line(state: &i32) -> [i32; 2] {
+        [state - 1, state + 1]
+    }
+
+    #[test]
+    fn test_bfs() {
+        let search = bfs(
+            [0],
+            |s| line(s).into_iter().filter(|n| n.abs() <= 10),
+            |s| *s == 4,
+        );
+        assert_eq!(search.goal_path(), Some(vec![0, 1, 2, 3, 4]));
+        assert_eq!(search.path_to(&-2), Some(vec![0, -1, -2]));
+
+        let all = bfs_all([0, 10], |s| {
+            line(s).into_iter().filter(|n| (0..=10).contains(n))
+        });
+        assert_eq!(all.len(), 11);
+        assert_eq!(all[&5], 5);
+        assert_eq!(all[&7], 3);
+    }
+
+    #[test]
+    fn test_astar() {
+        // with no heuristic it's plain dijkstra
+        let search = astar(['a'], weighted, |_| 0, |s| *s == 'd');
+        assert_eq!(search.goal_path(), Some(vec!['a', 'b', 'c', 'd']));
+        assert_eq!(search.path_to(&'z'), None);
+        assert_eq!(search.into_distances()[&'d'], 3);
+
+        let search = astar(
+            [0i32],
+            |s| line(s).map(|n| (n, 1)),
+            |s| (s - 7).unsigned_abs(),
+            |s| *s == 7,
+        );
+        assert_eq!(search.goal_path().map(|p| p.len()), Some(8));
+        // a good heuristic keeps the search from wandering off the other way
+        assert!(search.into_distances().keys().all(|s| *s >= -1));
+    }
+}
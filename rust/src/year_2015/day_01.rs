@@ -0,0 +1,78 @@
+use crate::solver::Solver;
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    input: Vec<i32>,
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.input = input
+            .chars()
+            .filter_map(|c| match c {
+                '(' => Some(1),
+                ')' => Some(-1),
+                _ => None,
+            })
+            .collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        self.input.iter().sum::<i32>().to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        self.input
+            .iter()
+            .scan(0, |floor, step| {
+                *floor += step;
+                Some(*floor)
+            })
+            .position(|floor| floor == -1)
+            .map(|i| (i + 1).to_string())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input("(()(()(");
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "3");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input("()())");
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "5");
+    }
+
+    #[test]
+    fn test_solution_part1() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "280");
+    }
+
+    #[test]
+    fn test_solution_part2() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "1797");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2015/day_01.txt");
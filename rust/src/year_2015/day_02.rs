@@ -0,0 +1,107 @@
+use crate::solver::Solver;
+
+#[derive(Debug)]
+struct Present {
+    l: u32,
+    w: u32,
+    h: u32,
+}
+
+impl Present {
+    fn wrapping_paper(&self) -> u32 {
+        let sides = [self.l * self.w, self.w * self.h, self.h * self.l];
+        sides.iter().map(|s| 2 * s).sum::<u32>() + sides.iter().min().unwrap()
+    }
+
+    fn ribbon(&self) -> u32 {
+        let mut dims = [self.l, self.w, self.h];
+        dims.sort_unstable();
+        2 * (dims[0] + dims[1]) + self.l * self.w * self.h
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    input: Vec<Present>,
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.input = input
+            .lines()
+            .map(|l| {
+                let mut dims = l.split('x').map(|d| d.parse().unwrap());
+                Present {
+                    l: dims.next().unwrap(),
+                    w: dims.next().unwrap(),
+                    h: dims.next().unwrap(),
+                }
+            })
+            .collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        self.input
+            .iter()
+            .map(Present::wrapping_paper)
+            .sum::<u32>()
+            .to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        self.input
+            .iter()
+            .map(Present::ribbon)
+            .sum::<u32>()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+2x3x4
+1x1x10"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "101");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "48");
+    }
+
+    #[test]
+    fn test_solution_part1() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "1606483");
+    }
+
+    #[test]
+    fn test_solution_part2() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "3842356");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2015/day_02.txt");
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use crate::{
+    solver::Solver,
+    utils::point::{Direction, Point2},
+};
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    input: Vec<Direction>,
+}
+
+fn visit_houses<'a>(
+    moves: impl Iterator<Item = &'a Direction>,
+    visited: &mut HashSet<Point2<i32>>,
+) {
+    let mut pos = Point2::default();
+    visited.insert(pos);
+    for dir in moves {
+        pos += dir.offset();
+        visited.insert(pos);
+    }
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.input = input
+            .chars()
+            .filter_map(|c| Direction::try_from(c).ok())
+            .collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        let mut visited = HashSet::new();
+        visit_houses(self.input.iter(), &mut visited);
+        visited.len().to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        let mut visited = HashSet::new();
+        visit_houses(self.input.iter().step_by(2), &mut visited);
+        visit_houses(self.input.iter().skip(1).step_by(2), &mut visited);
+        visited.len().to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solution_example1() {
+        for (input, expected) in [(">", "2"), ("^>v<", "4"), ("^v^v^v^v^v", "2")] {
+            let mut solver = Solution::default();
+            solver.with_input(input);
+            let solution = solver.solve_part1();
+            assert_eq!(solution, expected);
+        }
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        for (input, expected) in [("^v", "3"), ("^>v<", "3"), ("^v^v^v^v^v", "11")] {
+            let mut solver = Solution::default();
+            solver.with_input(input);
+            let solution = solver.solve_part2();
+            assert_eq!(solution, expected);
+        }
+    }
+
+    #[test]
+    fn test_solution_part1() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "2081");
+    }
+
+    #[test]
+    fn test_solution_part2() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "2341");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2015/day_03.txt");
@@ -0,0 +1,85 @@
+use std::io::Write;
+
+use crate::{
+    solver::Solver,
+    utils::{md5, parallel::par_find_first},
+};
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    secret: String,
+}
+
+fn has_leading_zeros(digest: &[u8; 16], zeros: usize) -> bool {
+    digest[..zeros / 2].iter().all(|b| *b == 0)
+        && (zeros.is_multiple_of(2) || digest[zeros / 2] >> 4 == 0)
+}
+
+impl Solution {
+    /// Finds the lowest nonce whose hash starts with `zeros` hex zeros.
+    fn mine(&self, zeros: usize) -> usize {
+        par_find_first(0..usize::MAX, |nonce| {
+            let mut buf = Vec::with_capacity(self.secret.len() + 20);
+            write!(buf, "{}{nonce}", self.secret).unwrap();
+            has_leading_zeros(&md5::digest(&buf), zeros).then_some(nonce)
+        })
+        .unwrap()
+    }
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.secret = input.trim().to_owned();
+    }
+
+    fn solve_part1(&self) -> String {
+        self.mine(5).to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        self.mine(6).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input("abcdef");
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "609043");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input("abcdef");
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "6742839");
+    }
+
+    #[test]
+    fn test_solution_part1() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "282749");
+    }
+
+    #[test]
+    fn test_solution_part2() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "9962624");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2015/day_04.txt");
@@ -0,0 +1,67 @@
+use crate::{solver::Solver, utils::math::mod_pow};
+
+const FIRST_CODE: u64 = 20151125;
+const MULTIPLIER: u64 = 252533;
+const MODULUS: u64 = 33554393;
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    row: u64,
+    col: u64,
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        let mut numbers = input
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse().unwrap());
+
+        self.row = numbers.next().unwrap();
+        self.col = numbers.next().unwrap();
+    }
+
+    fn solve_part1(&self) -> String {
+        // codes are filled in along anti-diagonals, so the index of (row, col)
+        // is the triangle number of the previous diagonal plus the column
+        let diagonal = self.row + self.col - 1;
+        let index = diagonal * (diagonal - 1) / 2 + self.col - 1;
+
+        (FIRST_CODE * mod_pow(MULTIPLIER, index, MODULUS) % MODULUS).to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        "".to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "row 4, column 4"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "9380097");
+    }
+
+    #[test]
+    fn test_solution_part1() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "19980801");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2015/day_25.txt");
@@ -1,4 +1,4 @@
-use crate::solver::Solver;
+use crate::{solver::Solver, utils::grid::Grid};
 
 #[derive(Debug)]
 enum Space {
@@ -17,20 +17,21 @@ impl From<char> for Space {
 
 #[derive(Default, Debug)]
 struct Hill {
-    spaces: Vec<Vec<Space>>,
+    spaces: Grid<Space>,
 }
 
 impl Hill {
     fn count_trees(&self, right: usize, down: usize) -> usize {
-        let mut r = 0;
-        self.spaces.iter().step_by(down).fold(0, |tree_count, row| {
-            let space = row.get(r % row.len()).unwrap();
-            r += right;
-            match space {
-                Space::Tree => tree_count + 1,
-                Space::Open => tree_count,
-            }
-        })
+        (0..self.spaces.height())
+            .step_by(down)
+            .enumerate()
+            .filter(|(step, row)| {
+                let space = self
+                    .spaces
+                    .get_wrapping(*row as isize, (step * right) as isize);
+                matches!(space, Some(Space::Tree))
+            })
+            .count()
     }
 }
 
@@ -46,10 +47,7 @@ impl Solver for Solution {
 
     fn with_input(&mut self, input: &str) {
         self.input = Hill {
-            spaces: input
-                .lines()
-                .map(|l| l.chars().map(Space::from).collect())
-                .collect(),
+            spaces: Grid::parse(input, Space::from),
         }
     }
 
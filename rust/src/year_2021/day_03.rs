@@ -1,14 +1,18 @@
-use crate::solver::Solver;
+use crate::{solver::Solver, utils::grid::Grid};
 
 #[derive(Default, Debug, Clone)]
-pub struct BinaryArray(Vec<Vec<u8>>);
+pub struct BinaryArray(Grid<u8>);
 
 impl BinaryArray {
-    fn count_ones_in_column(&self, column: usize) -> usize {
+    fn count_ones_in_column(&self, rows: &[usize], column: usize) -> usize {
+        rows.iter().map(|r| self.0[(*r, column)] as usize).sum()
+    }
+
+    fn row_value(&self, row: usize) -> usize {
         self.0
+            .row(row)
             .iter()
-            .map(|l| *l.get(column).unwrap() as usize)
-            .sum::<usize>()
+            .fold(0, |acc, bit| acc * 2 + *bit as usize)
     }
 }
 
@@ -23,25 +27,16 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.input = BinaryArray(
-            input
-                .lines()
-                .map(|l| {
-                    l.chars()
-                        .map(|c| if c == '1' { 1 } else { 0 })
-                        .collect::<Vec<u8>>()
-                })
-                .collect::<Vec<Vec<u8>>>(),
-        );
+        self.input = BinaryArray(Grid::parse(input, |c| if c == '1' { 1 } else { 0 }));
     }
 
     fn solve_part1(&self) -> String {
-        let rows = self.input.0.len();
-        let cols = self.input.0.first().unwrap().len();
+        let rows = self.input.0.height();
+        let cols = self.input.0.width();
 
         let gamma = (0..cols)
             .map(|c| {
-                let ones_count = self.input.count_ones_in_column(c);
+                let ones_count = self.input.0.col(c).filter(|b| **b == 1).count();
 
                 if ones_count > rows / 2 {
                     2usize.pow((cols - c - 1) as u32)
@@ -56,14 +51,14 @@ impl Solver for Solution {
     }
 
     fn solve_part2(&self) -> String {
-        let cols = self.input.0.first().unwrap().len();
-        let mut oxygen_rating = self.input.clone();
-        let mut co2_rating = self.input.clone();
+        let cols = self.input.0.width();
+        let mut oxygen_rating = (0..self.input.0.height()).collect::<Vec<usize>>();
+        let mut co2_rating = oxygen_rating.clone();
 
         for c in 0..cols {
-            if oxygen_rating.0.len() > 1 {
-                let rows = oxygen_rating.0.len();
-                let target = if oxygen_rating.count_ones_in_column(c)
+            if oxygen_rating.len() > 1 {
+                let rows = oxygen_rating.len();
+                let target = if self.input.count_ones_in_column(&oxygen_rating, c)
                     >= (rows as f32 / 2f32).ceil() as usize
                 {
                     1
@@ -71,12 +66,12 @@ impl Solver for Solution {
                     0
                 };
 
-                oxygen_rating.0.retain(|l| *l.get(c).unwrap() == target);
+                oxygen_rating.retain(|r| self.input.0[(*r, c)] == target);
             }
 
-            if co2_rating.0.len() > 1 {
-                let rows = co2_rating.0.len();
-                let target = if rows - co2_rating.count_ones_in_column(c)
+            if co2_rating.len() > 1 {
+                let rows = co2_rating.len();
+                let target = if rows - self.input.count_ones_in_column(&co2_rating, c)
                     >= (rows as f32 / 2f32).ceil() as usize
                 {
                     0
@@ -84,27 +79,12 @@ impl Solver for Solution {
                     1
                 };
 
-                co2_rating.0.retain(|l| *l.get(c).unwrap() == target);
+                co2_rating.retain(|r| self.input.0[(*r, c)] == target);
             }
         }
 
-        let oxygen_rating = oxygen_rating
-            .0
-            .first()
-            .unwrap()
-            .iter()
-            .enumerate()
-            .map(|(i, v)| *v as usize * 2usize.pow((cols - i - 1) as u32))
-            .sum::<usize>();
-
-        let co2_rating = co2_rating
-            .0
-            .first()
-            .unwrap()
-            .iter()
-            .enumerate()
-            .map(|(i, v)| *v as usize * 2usize.pow((cols - i - 1) as u32))
-            .sum::<usize>();
+        let oxygen_rating = self.input.row_value(oxygen_rating[0]);
+        let co2_rating = self.input.row_value(co2_rating[0]);
 
         println!("oxygen_rating: {}", oxygen_rating);
         println!("co2_rating: {}", co2_rating);
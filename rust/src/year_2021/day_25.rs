@@ -1,16 +1,63 @@
-use std::collections::HashSet;
+use crate::{solver::Solver, utils::bitset::BitGrid};
 
-use crate::solver::Solver;
+#[derive(Debug, Clone, Copy)]
+enum Herd {
+    East,
+    South,
+}
+
+/// Where each herd of sea cucumbers is, indexed `[row, col]`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+struct Seafloor {
+    east: BitGrid<2>,
+    south: BitGrid<2>,
+}
+
+impl Seafloor {
+    fn herd(&self, herd: Herd) -> &BitGrid<2> {
+        match herd {
+            Herd::East => &self.east,
+            Herd::South => &self.south,
+        }
+    }
+
+    fn is_empty(&self, cell: [usize; 2]) -> bool {
+        !self.east.contains(cell) && !self.south.contains(cell)
+    }
 
-#[derive(Debug, Hash, PartialEq, Eq, Copy, Clone)]
-struct Pos(usize, usize);
+    /// Moves every cucumber of one herd that has space in front of it, all
+    /// at once, returning whether any of them moved.
+    fn step_herd(&mut self, herd: Herd) -> bool {
+        let [height, width] = self.east.dims();
+        let moves = self
+            .herd(herd)
+            .iter()
+            .map(|[row, col]| {
+                let target = match herd {
+                    Herd::East => [row, (col + 1) % width],
+                    Herd::South => [(row + 1) % height, col],
+                };
+                ([row, col], target)
+            })
+            .filter(|(_, target)| self.is_empty(*target))
+            .collect::<Vec<_>>();
+
+        let cells = match herd {
+            Herd::East => &mut self.east,
+            Herd::South => &mut self.south,
+        };
+        let moved = !moves.is_empty();
+        for (from, to) in moves {
+            cells.remove(from);
+            cells.insert(to);
+        }
+        moved
+    }
+}
 
 #[derive(Default, Debug)]
 pub struct Solution {
-    east_cukes: HashSet<Pos>,
-    south_cukes: HashSet<Pos>,
-    width: usize,
-    height: usize,
+    seafloor: Seafloor,
 }
 
 impl Solver for Solution {
@@ -19,61 +66,33 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        input.lines().enumerate().for_each(|(y, row)| {
-            self.height += 1;
-            self.width = row.trim().len();
-
-            row.chars().enumerate().for_each(|(x, col)| {
-                match col {
-                    '>' => {
-                        self.east_cukes.insert(Pos(x, y));
-                    }
-                    'v' => {
-                        self.south_cukes.insert(Pos(x, y));
-                    }
-                    _ => {}
+        let lines = input.trim().lines().collect::<Vec<_>>();
+        let dims = [lines.len(), lines.first().map_or(0, |l| l.len())];
+        let mut seafloor = Seafloor {
+            east: BitGrid::new(dims),
+            south: BitGrid::new(dims),
+        };
+        for (row, line) in lines.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    '>' => seafloor.east.insert([row, col]),
+                    'v' => seafloor.south.insert([row, col]),
+                    _ => false,
                 };
-            })
-        });
+            }
+        }
+        self.seafloor = seafloor;
     }
 
     fn solve_part1(&self) -> String {
-        let mut next_east_cukes = self.east_cukes.clone();
-        let mut next_south_cukes = self.south_cukes.clone();
-        let mut moved = true;
-        let mut round = 0;
-        while moved {
-            moved = false;
-            next_east_cukes = next_east_cukes
-                .iter()
-                .map(|c| {
-                    let target = Pos((c.0 + 1) % self.width, c.1);
-                    if !next_east_cukes.contains(&target) && !next_south_cukes.contains(&target) {
-                        moved = true;
-                        target
-                    } else {
-                        *c
-                    }
-                })
-                .collect();
-
-            next_south_cukes = next_south_cukes
-                .iter()
-                .map(|c| {
-                    let target = Pos(c.0, (c.1 + 1) % self.height);
-                    if !next_east_cukes.contains(&target) && !next_south_cukes.contains(&target) {
-                        moved = true;
-                        target
-                    } else {
-                        *c
-                    }
-                })
-                .collect();
-
-            round += 1;
+        let mut seafloor = self.seafloor.clone();
+        let mut steps = 1;
+        // both herds get their turn, even once the east one is stuck
+        while seafloor.step_herd(Herd::East) | seafloor.step_herd(Herd::South) {
+            steps += 1;
         }
 
-        round.to_string()
+        steps.to_string()
     }
 
     fn solve_part2(&self) -> String {
@@ -19,3 +19,13 @@ pub mod day_18;
 pub mod day_19;
 pub mod day_20;
 pub mod day_21;
+// no puzzle inputs for these yet, so they're only built for their example
+// tests and aren't registered with the solver
+#[cfg(test)]
+pub mod day_22;
+#[cfg(test)]
+pub mod day_23;
+#[cfg(test)]
+pub mod day_24;
+#[cfg(test)]
+pub mod day_25;
@@ -1,32 +1,20 @@
-use std::str::FromStr;
+use std::ops::RangeInclusive;
 
 use crate::solver::Solver;
 
-struct Section {
-    start: usize,
-    end: usize,
-}
+type Section = RangeInclusive<usize>;
 
-impl Section {
-    fn is_contained_by(&self, section: &Section) -> bool {
-        self.start >= section.start && self.end <= section.end
-    }
+fn parse_section(s: &str) -> Section {
+    let (start, end) = s.split_once('-').unwrap();
+    start.parse::<usize>().unwrap()..=end.parse::<usize>().unwrap()
+}
 
-    fn is_overlapped_by(&self, section: &Section) -> bool {
-        (self.start >= section.start && self.start <= section.end)
-            || (self.end >= section.start && self.end <= section.end)
-    }
+fn contains(outer: &Section, inner: &Section) -> bool {
+    outer.start() <= inner.start() && inner.end() <= outer.end()
 }
 
-impl FromStr for Section {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s.split_once('-').unwrap();
-        Ok(Section {
-            start: start.parse::<usize>().unwrap(),
-            end: end.parse::<usize>().unwrap(),
-        })
-    }
+fn overlaps(a: &Section, b: &Section) -> bool {
+    a.start() <= b.end() && b.start() <= a.end()
 }
 
 #[derive(Default)]
@@ -44,10 +32,7 @@ impl Solver for Solution {
             .lines()
             .map(|l| {
                 let (first, second) = l.split_once(',').unwrap();
-                (
-                    first.parse::<Section>().unwrap(),
-                    second.parse::<Section>().unwrap(),
-                )
+                (parse_section(first), parse_section(second))
             })
             .collect();
     }
@@ -55,18 +40,14 @@ impl Solver for Solution {
     fn solve_part1(&self) -> String {
         self.input
             .iter()
-            .filter(|(first, second)| {
-                first.is_contained_by(second) || second.is_contained_by(first)
-            })
+            .filter(|(first, second)| contains(second, first) || contains(first, second))
             .count()
             .to_string()
     }
     fn solve_part2(&self) -> String {
         self.input
             .iter()
-            .filter(|(first, second)| {
-                first.is_overlapped_by(second) || second.is_contained_by(first)
-            })
+            .filter(|(first, second)| overlaps(first, second))
             .count()
             .to_string()
     }
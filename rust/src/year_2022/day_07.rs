@@ -1,78 +1,238 @@
-use std::{collections::HashMap, str::FromStr, vec::Drain};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+    str::FromStr,
+};
 
 use crate::solver::Solver;
 
-#[derive(Debug, Default)]
-struct Directory {
-    children: HashMap<String, Directory>,
+const SMALL_DIR_LIMIT: usize = 100000;
+const DISK_SIZE: usize = 70000000;
+const UPDATE_SIZE: usize = 30000000;
+
+type NodeId = usize;
+
+const ROOT: NodeId = 0;
+
+#[derive(Debug)]
+enum NodeKind {
+    // children are kept sorted by name so listings come out the same way
+    // every time
+    Dir(BTreeMap<String, NodeId>),
+    File,
+}
+
+#[derive(Debug)]
+struct Node {
+    name: String,
+    parent: Option<NodeId>,
+    kind: NodeKind,
+    // for a directory, the total of everything under it
     size: usize,
 }
 
-impl Directory {
-    fn iter(&self) -> DirectoryIter {
-        DirectoryIter::new(self)
+/// The filesystem a terminal transcript explored, as an arena of nodes with
+/// the root directory at [`ROOT`]. Anything the transcript never listed just
+/// isn't there, so sizes only count what was seen.
+#[derive(Debug)]
+struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                kind: NodeKind::Dir(BTreeMap::new()),
+                size: 0,
+            }],
+        }
     }
 }
 
-fn create_dir(input: &mut Drain<&str>, dir: &mut Directory) -> usize {
-    while let Some(next) = input.next() {
-        let com = next.parse::<Command>().unwrap();
-        match com {
-            Command::CD(name) => {
-                if name == ".." {
-                    return dir.size;
-                } else if name != "/" {
-                    dir.size += create_dir(input, dir.children.get_mut(&name).unwrap());
-                }
-            }
-            Command::LS => {}
-            Command::Dir(name) => {
-                dir.children.insert(
-                    name,
-                    Directory {
-                        children: HashMap::new(),
-                        size: 0,
-                    },
-                );
+impl FileSystem {
+    fn size(&self, id: NodeId) -> usize {
+        self.nodes[id].size
+    }
+
+    fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Dir(_))
+    }
+
+    fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let children = match &self.nodes[id].kind {
+            NodeKind::Dir(children) => Some(children.values().copied()),
+            NodeKind::File => None,
+        };
+        children.into_iter().flatten()
+    }
+
+    fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        match &self.nodes[dir].kind {
+            NodeKind::Dir(children) => children.get(name).copied(),
+            NodeKind::File => None,
+        }
+    }
+
+    /// Every directory, parents before their children.
+    fn directories(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|&id| self.is_dir(id))
+    }
+
+    /// The absolute path of a node, `/` for the root.
+    fn path(&self, id: NodeId) -> String {
+        let mut names = Vec::new();
+        let mut node = id;
+        while let Some(parent) = self.nodes[node].parent {
+            names.push(self.nodes[node].name.as_str());
+            node = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Follows a path from `from`, or from the root if it starts with `/`.
+    fn resolve(&self, from: NodeId, path: &str) -> Option<NodeId> {
+        let start = if path.starts_with('/') { ROOT } else { from };
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(start, |node, name| match name {
+                "." => Some(node),
+                ".." => Some(self.nodes[node].parent.unwrap_or(ROOT)),
+                name => self.child(node, name),
+            })
+    }
+
+    /// The child called `name` in `dir`, which is created as a directory if
+    /// there isn't one yet.
+    fn make_dir(&mut self, dir: NodeId, name: &str) -> Result<NodeId, String> {
+        match self.child(dir, name) {
+            Some(id) if self.is_dir(id) => Ok(id),
+            Some(_) => Err(format!("{} is a file", self.path(dir) + "/" + name)),
+            None => Ok(self.add_node(dir, name, NodeKind::Dir(BTreeMap::new()), 0)),
+        }
+    }
+
+    /// Records a file in `dir`. Listing the same file again replaces its
+    /// size rather than counting it twice.
+    fn add_file(&mut self, dir: NodeId, name: &str, size: usize) -> Result<NodeId, String> {
+        let id = match self.child(dir, name) {
+            Some(id) if self.is_dir(id) => {
+                return Err(format!("{} is a directory", self.path(id)));
             }
-            Command::File(size) => dir.size += size,
+            Some(id) => id,
+            None => self.add_node(dir, name, NodeKind::File, 0),
         };
+
+        let old_size = self.nodes[id].size;
+        let mut node = Some(id);
+        while let Some(id) = node {
+            self.nodes[id].size = self.nodes[id].size - old_size + size;
+            node = self.nodes[id].parent;
+        }
+        Ok(id)
     }
 
-    dir.size
-}
+    fn add_node(&mut self, dir: NodeId, name: &str, kind: NodeKind, size: usize) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(dir),
+            kind,
+            size,
+        });
+        if let NodeKind::Dir(children) = &mut self.nodes[dir].kind {
+            children.insert(name.to_string(), id);
+        }
+        id
+    }
 
-impl From<&str> for Directory {
-    fn from(input: &str) -> Self {
-        let mut dir = Directory::default();
-        let mut lines = input.lines().collect::<Vec<&str>>();
-        let mut input = lines.drain(0..);
+    /// The smallest directory that would leave at least `needed` bytes free
+    /// on a disk of `disk_size` bytes if it were deleted.
+    fn smallest_dir_freeing(&self, disk_size: usize, needed: usize) -> Option<NodeId> {
+        let free = disk_size.saturating_sub(self.size(ROOT));
+        let to_delete = needed.saturating_sub(free);
+        self.directories()
+            .filter(|&id| self.size(id) >= to_delete)
+            .min_by_key(|&id| self.size(id))
+    }
 
-        create_dir(&mut input, &mut dir);
-        dir
+    /// Directory totals under `path` the way `du` prints them, children
+    /// before parents, or `None` if there's nothing at `path`.
+    fn du(&self, path: &str) -> Option<String> {
+        let mut out = String::new();
+        self.du_from(self.resolve(ROOT, path)?, &mut out);
+        Some(out)
+    }
+
+    fn du_from(&self, dir: NodeId, out: &mut String) {
+        for child in self.children(dir).filter(|&id| self.is_dir(id)) {
+            self.du_from(child, out);
+        }
+        writeln!(out, "{}\t{}", self.size(dir), self.path(dir)).unwrap();
     }
-}
 
-struct DirectoryIter<'a> {
-    stack: Vec<&'a Directory>,
+    fn tree_from(
+        &self,
+        id: NodeId,
+        depth: usize,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        let node = &self.nodes[id];
+        let indent = "  ".repeat(depth);
+        match node.kind {
+            NodeKind::Dir(_) => writeln!(f, "{indent}- {} (dir)", node.name)?,
+            NodeKind::File => writeln!(f, "{indent}- {} (file, size={})", node.name, node.size)?,
+        }
+        self.children(id)
+            .try_for_each(|child| self.tree_from(child, depth + 1, f))
+    }
 }
 
-impl<'a> DirectoryIter<'a> {
-    fn new(root: &'a Directory) -> Self {
-        Self { stack: vec![root] }
+/// The whole filesystem drawn like the puzzle text does.
+impl Display for FileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.tree_from(ROOT, 0, f)
     }
 }
 
-impl<'a> Iterator for DirectoryIter<'a> {
-    type Item = &'a Directory;
+impl FromStr for FileSystem {
+    type Err = String;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(d) = self.stack.pop() {
-            self.stack.extend(d.children.values());
-            Some(d)
-        } else {
-            None
+    /// Replays a terminal transcript. `cd` into a directory that was never
+    /// listed creates it, and `cd ..` at the root stays put, like a real
+    /// shell would.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fs = FileSystem::default();
+        let mut cwd = ROOT;
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            match line.parse::<Command>()? {
+                Command::CD(path) => {
+                    if path.starts_with('/') {
+                        cwd = ROOT;
+                    }
+                    for name in path.split('/').filter(|name| !name.is_empty()) {
+                        cwd = match name {
+                            "." => cwd,
+                            ".." => fs.nodes[cwd].parent.unwrap_or(ROOT),
+                            name => fs.make_dir(cwd, name)?,
+                        };
+                    }
+                }
+                Command::LS => {}
+                Command::Dir(name) => {
+                    fs.make_dir(cwd, &name)?;
+                }
+                Command::File(name, size) => {
+                    fs.add_file(cwd, &name, size)?;
+                }
+            }
         }
+
+        Ok(fs)
     }
 }
 
@@ -81,31 +241,31 @@ enum Command {
     CD(String),
     LS,
     Dir(String),
-    File(usize),
+    File(String, usize),
 }
 
 impl FromStr for Command {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with("$ cd") {
-            Ok(Command::CD(s.trim_start_matches("$ cd ").to_string()))
-        } else if s.starts_with("$ ls") {
+        if let Some(path) = s.strip_prefix("$ cd ") {
+            Ok(Command::CD(path.trim().to_string()))
+        } else if s.trim() == "$ ls" {
             Ok(Command::LS)
-        } else if s.starts_with("dir") {
-            Ok(Command::Dir(s.trim_start_matches("dir ").to_string()))
+        } else if let Some(name) = s.strip_prefix("dir ") {
+            Ok(Command::Dir(name.trim().to_string()))
         } else {
-            let (size, _name) = s
+            let (size, name) = s
                 .split_once(' ')
-                .ok_or_else(|| "bad file descriptor!".to_string())?;
-            let size = size.parse::<usize>().map_err(|e| e.to_string())?;
-            Ok(Command::File(size))
+                .ok_or_else(|| format!("bad file descriptor: {s}"))?;
+            let size = size.parse::<usize>().map_err(|e| format!("{e}: {s}"))?;
+            Ok(Command::File(name.trim().to_string(), size))
         }
     }
 }
 
 #[derive(Default)]
 pub struct Solution {
-    directory: Directory,
+    filesystem: FileSystem,
 }
 
 impl Solver for Solution {
@@ -114,34 +274,31 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.directory = Directory::from(input);
+        self.filesystem = input.parse().unwrap();
     }
 
     fn solve_part1(&self) -> String {
-        let target_size = 100000;
-        self.directory
-            .iter()
-            .filter(|d| d.size <= target_size)
-            .map(|d| d.size)
+        let fs = &self.filesystem;
+        fs.directories()
+            .map(|id| fs.size(id))
+            .filter(|&size| size <= SMALL_DIR_LIMIT)
             .sum::<usize>()
             .to_string()
     }
 
     fn solve_part2(&self) -> String {
-        let total_space = 70000000;
-        let target_free_space = 30000000;
-        let current_used_space = self.directory.size;
-        let amount_to_clean_up = target_free_space - (total_space - current_used_space);
-
-        let mut big_dirs = self
-            .directory
-            .iter()
-            .filter(|d| d.size >= amount_to_clean_up)
-            .collect::<Vec<&Directory>>();
-
-        big_dirs.sort_by_key(|d| d.size);
+        let fs = &self.filesystem;
+        let dir = fs
+            .smallest_dir_freeing(DISK_SIZE, UPDATE_SIZE)
+            .expect("no directory is big enough");
+        fs.size(dir).to_string()
+    }
+}
 
-        big_dirs[0].size.to_string()
+/// Every directory's total, which is what both parts are picking through.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.filesystem.du("/").unwrap_or_default())
     }
 }
 
@@ -180,7 +337,8 @@ $ ls
     fn test_parse() {
         let mut solver = Solution::default();
         solver.with_input(get_input());
-        assert_eq!(solver.directory.size, 48381165);
+        assert_eq!(solver.filesystem.size(ROOT), 48381165);
+        assert!(solver.to_string().ends_with("24933642\t/d\n48381165\t/\n"));
     }
 
     #[test]
@@ -198,6 +356,85 @@ $ ls
         let solution = solver.solve_part2();
         assert_eq!(solution, "24933642");
     }
+
+    #[test]
+    fn test_rendering() {
+        let fs = get_input().parse::<FileSystem>().unwrap();
+        assert_eq!(
+            fs.to_string(),
+            "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+"
+        );
+        assert_eq!(
+            fs.du("/").unwrap(),
+            "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/\n"
+        );
+        assert_eq!(fs.du("/a").unwrap(), "584\t/a/e\n94853\t/a\n");
+        assert_eq!(fs.du("/x"), None);
+    }
+
+    #[test]
+    fn test_paths() {
+        let fs = get_input().parse::<FileSystem>().unwrap();
+        let e = fs.resolve(ROOT, "/a/e").unwrap();
+        assert_eq!(fs.path(e), "/a/e");
+        assert_eq!(
+            fs.resolve(e, "../../d/j").map(|j| fs.size(j)),
+            Some(4060174)
+        );
+        assert_eq!(fs.resolve(e, "missing"), None);
+
+        let freed = fs.smallest_dir_freeing(DISK_SIZE, UPDATE_SIZE);
+        assert_eq!(freed.map(|id| fs.path(id)), Some("/d".to_string()));
+        // enough room already, so the smallest directory of all will do
+        assert_eq!(fs.smallest_dir_freeing(DISK_SIZE, 100), Some(e));
+        assert_eq!(fs.smallest_dir_freeing(DISK_SIZE, DISK_SIZE), Some(ROOT));
+        assert_eq!(fs.smallest_dir_freeing(DISK_SIZE, DISK_SIZE + 1), None);
+    }
+
+    #[test]
+    fn test_messy_transcript() {
+        // unlisted directories, jumping back to the root and listing twice
+        let fs = "\
+$ cd ..
+$ cd x/y
+$ ls
+10 a
+$ cd /
+$ ls
+dir x
+5 b
+$ ls
+dir x
+5 b
+$ cd /x/y/..
+$ ls
+20 c
+$ cd y
+$ ls
+15 a"
+            .parse::<FileSystem>()
+            .unwrap();
+
+        assert_eq!(fs.size(ROOT), 40);
+        assert_eq!(fs.du("/").unwrap(), "15\t/x/y\n35\t/x\n40\t/\n");
+        assert!("$ cd /\n$ ls\n1 a\n$ cd a".parse::<FileSystem>().is_err());
+        assert!("$ cd /\n$ ls\nten a".parse::<FileSystem>().is_err());
+    }
 }
 
 const INPUT: &str = "$ cd /
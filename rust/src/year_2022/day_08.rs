@@ -1,4 +1,4 @@
-use crate::solver::Solver;
+use crate::{solver::Solver, utils::grid::Grid};
 
 enum Direction {
     Up,
@@ -7,65 +7,29 @@ enum Direction {
     Right,
 }
 
-type Tree = u8;
-
-#[derive(Default, Debug)]
-struct Forest {
-    trees: Vec<Vec<u8>>,
-    rows_len: usize,
-    cols_len: usize,
-}
-
-impl Forest {
-    fn iter_dir<'a>(&'a self, row: usize, col: usize, dir: &'a Direction) -> ForestDirectionalIter {
-        ForestDirectionalIter::new(self, row, col, dir)
-    }
-
-    fn get(&self, row: usize, col: usize) -> Option<&Tree> {
-        self.trees.get(row)?.get(col)
-    }
-}
-
-struct ForestDirectionalIter<'a> {
-    forest: &'a Forest,
-    row: isize,
-    col: isize,
-    dir: &'a Direction,
-}
-
-impl<'a> ForestDirectionalIter<'a> {
-    fn new(forest: &'a Forest, row: usize, col: usize, dir: &'a Direction) -> Self {
-        Self {
-            forest,
-            row: row as isize,
-            col: col as isize,
-            dir,
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
         }
     }
 }
 
-impl<'a> Iterator for ForestDirectionalIter<'a> {
-    type Item = &'a Tree;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.dir {
-            Direction::Up => self.row -= 1,
-            Direction::Down => self.row += 1,
-            Direction::Left => self.col -= 1,
-            Direction::Right => self.col += 1,
-        };
-
-        if self.row < 0 || self.col < 0 {
-            return None;
-        }
-
-        self.forest.get(self.row as usize, self.col as usize)
-    }
-}
+type Tree = u8;
 
 #[derive(Default)]
 pub struct Solution {
-    forest: Forest,
+    forest: Grid<Tree>,
 }
 
 impl Solver for Solution {
@@ -74,43 +38,17 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.forest.trees = input
-            .lines()
-            .map(|l| {
-                l.chars()
-                    .map(|c| c.to_digit(10).unwrap() as Tree)
-                    .collect::<Vec<Tree>>()
-            })
-            .collect::<Vec<Vec<Tree>>>();
-        self.forest.rows_len = self.forest.trees.len();
-        self.forest.cols_len = self.forest.trees.first().unwrap().len();
+        self.forest = Grid::parse(input, |c| c.to_digit(10).unwrap() as Tree);
     }
 
     fn solve_part1(&self) -> String {
         let visible_trees = self
             .forest
-            .trees
             .iter()
-            .enumerate()
-            .flat_map(|(r, row)| {
-                row.iter().enumerate().filter(move |(c, tree)| {
-                    vec![
-                        Direction::Up,
-                        Direction::Down,
-                        Direction::Left,
-                        Direction::Right,
-                    ]
+            .filter(|(pos, tree)| {
+                Direction::ALL
                     .iter()
-                    .any(|dir| {
-                        let mut iter = self.forest.iter_dir(r, *c, dir).peekable();
-
-                        if iter.peek().is_none() {
-                            true
-                        } else {
-                            iter.all(|t| tree > &t)
-                        }
-                    })
-                })
+                    .any(|dir| self.forest.ray(*pos, dir.offset()).all(|(_, t)| *tree > t))
             })
             .count();
 
@@ -118,45 +56,26 @@ impl Solver for Solution {
     }
 
     fn solve_part2(&self) -> String {
-        let mut scenic_scores = self
-            .forest
-            .trees
+        self.forest
             .iter()
-            .enumerate()
-            .flat_map(|(r, row)| {
-                row.iter()
-                    .enumerate()
-                    .map(|(c, tree)| {
-                        let scores = vec![
-                            Direction::Up,
-                            Direction::Down,
-                            Direction::Left,
-                            Direction::Right,
-                        ]
-                        .iter()
-                        .map(|dir| {
-                            let iter = self.forest.iter_dir(r, c, dir);
-
-                            let mut score = 0;
-                            for t in iter {
-                                score += 1;
-                                if t >= tree {
-                                    break;
-                                }
+            .map(|(pos, tree)| {
+                Direction::ALL
+                    .iter()
+                    .map(|dir| {
+                        let mut score = 0;
+                        for (_, t) in self.forest.ray(pos, dir.offset()) {
+                            score += 1;
+                            if t >= tree {
+                                break;
                             }
-                            score
-                        })
-                        .collect::<Vec<usize>>();
-
-                        scores.iter().product()
+                        }
+                        score
                     })
-                    .collect::<Vec<usize>>()
+                    .product::<usize>()
             })
-            .collect::<Vec<usize>>();
-
-        scenic_scores.sort();
-
-        scenic_scores.last().unwrap().to_string()
+            .max()
+            .unwrap()
+            .to_string()
     }
 }
 
@@ -1,38 +1,23 @@
-use std::{collections::HashSet, fmt::Display, primitive::f32, str::FromStr};
+use std::{collections::HashSet, fmt::Display, str::FromStr};
 
-use crate::solver::Solver;
+use crate::{
+    solver::Solver,
+    utils::point::{Direction8, Point2},
+};
 
-#[derive(Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl FromStr for Direction {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "U" => Ok(Direction::Up),
-            "D" => Ok(Direction::Down),
-            "L" => Ok(Direction::Left),
-            "R" => Ok(Direction::Right),
-            _ => Err(format!("UNKNOWN DIRECTION: {s}")),
-        }
-    }
-}
+const PART1_KNOTS: usize = 2;
+const PART2_KNOTS: usize = 10;
 
 #[derive(Debug)]
 struct Move {
-    dir: Direction,
+    dir: Direction8,
     amt: usize,
 }
 
 impl FromStr for Move {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (dir, amt) = s.split_once(' ').unwrap();
+        let (dir, amt) = s.split_once(' ').ok_or_else(|| format!("bad move: {s}"))?;
         Ok(Self {
             dir: dir.parse()?,
             amt: amt.parse().map_err(|e| format!("{e:?}"))?,
@@ -40,36 +25,134 @@ impl FromStr for Move {
     }
 }
 
-#[derive(Eq, Hash, PartialEq, Clone, Debug)]
-struct Position(isize, isize);
+/// Written back the way the input has it.
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dir = match self.dir {
+            Direction8::N => "U",
+            Direction8::NE => "UR",
+            Direction8::E => "R",
+            Direction8::SE => "DR",
+            Direction8::S => "D",
+            Direction8::SW => "DL",
+            Direction8::W => "L",
+            Direction8::NW => "UL",
+        };
+        write!(f, "{dir} {}", self.amt)
+    }
+}
+
+type Position = Point2<isize>;
 
-impl Position {
-    fn move_closer_to(&mut self, other: &Position) {
-        if self.distance_to(other) >= 2.0 {
-            self.0 += (other.0 - self.0).signum();
-            self.1 += (other.1 - self.1).signum();
+/// A rope of knots that all start on the same spot. The head is moved one
+/// step at a time and every other knot follows the one in front of it,
+/// remembering every position it's been in.
+#[derive(Debug, Clone)]
+struct Rope {
+    knots: Vec<Position>,
+    visited: Vec<HashSet<Position>>,
+}
+
+impl Rope {
+    fn new(knots: usize) -> Self {
+        assert!(knots > 0, "a rope needs at least a head");
+        let start = Position::default();
+        Self {
+            knots: vec![start; knots],
+            visited: vec![HashSet::from([start]); knots],
         }
     }
 
-    fn move_by_dir(&mut self, dir: &Direction) {
-        match dir {
-            Direction::Up => self.1 += -1,
-            Direction::Down => self.1 += 1,
-            Direction::Left => self.0 += -1,
-            Direction::Right => self.0 += 1,
-        };
+    fn tail(&self) -> usize {
+        self.knots.len() - 1
     }
 
-    fn distance_to(&self, other: &Position) -> f32 {
-        f32::sqrt(
-            f32::powf((other.0 - self.0) as f32, 2.0) + f32::powf((other.1 - self.1) as f32, 2.0),
-        )
+    fn visited(&self, knot: usize) -> &HashSet<Position> {
+        &self.visited[knot]
+    }
+
+    fn step(&mut self, dir: Direction8) {
+        self.knots[0] += dir.offset();
+        self.visited[0].insert(self.knots[0]);
+        for i in 1..self.knots.len() {
+            let ahead = self.knots[i - 1];
+            let knot = &mut self.knots[i];
+            if knot.is_touching(&ahead) {
+                // nothing further back can move either
+                break;
+            }
+            *knot += (ahead - *knot).signum();
+            self.visited[i].insert(*knot);
+        }
+    }
+
+    fn apply(&mut self, m: &Move) {
+        self.apply_with(m, |_| {});
+    }
+
+    /// Like [`Rope::apply`], showing the rope to `on_step` after every step.
+    fn apply_with(&mut self, m: &Move, mut on_step: impl FnMut(&Rope)) {
+        for _ in 0..m.amt {
+            self.step(m.dir);
+            on_step(self);
+        }
     }
 }
 
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}:{}", self.0, self.1)
+// drawing, for checking against the puzzle's diagrams
+impl Rope {
+    /// The smallest area holding the start, every knot and everywhere any
+    /// of them has been.
+    fn bounds(&self) -> (Position, Position) {
+        self.visited.iter().flatten().fold(
+            (Position::default(), Position::default()),
+            |(min, max), p| {
+                (
+                    Position::new(min.x.min(p.x), min.y.min(p.y)),
+                    Position::new(max.x.max(p.x), max.y.max(p.y)),
+                )
+            },
+        )
+    }
+
+    fn render_with(
+        &self,
+        (min, max): (Position, Position),
+        cell: impl Fn(Position) -> Option<char>,
+    ) -> String {
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| {
+                        let p = Position::new(x, y);
+                        cell(p).unwrap_or(if p == Position::default() { 's' } else { '.' })
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The knots drawn the way the puzzle does: `H` for the head, then `T`
+    /// for the tail of a two knot rope or numbers for a longer one, with `s`
+    /// marking the start. Where knots overlap the one nearer the head wins.
+    fn render(&self, bounds: (Position, Position)) -> String {
+        let label = |i: usize| match i {
+            0 => 'H',
+            1 if self.knots.len() == 2 => 'T',
+            i => char::from_digit(i as u32 % 36, 36).unwrap(),
+        };
+        self.render_with(bounds, |p| {
+            self.knots.iter().position(|k| *k == p).map(label)
+        })
+    }
+
+    /// Everywhere `knot` has been marked with `#`, apart from the start.
+    fn render_trail(&self, knot: usize, bounds: (Position, Position)) -> String {
+        let visited = &self.visited[knot];
+        self.render_with(bounds, |p| {
+            (p != Position::default() && visited.contains(&p)).then_some('#')
+        })
     }
 }
 
@@ -78,6 +161,40 @@ pub struct Solution {
     input: Vec<Move>,
 }
 
+impl Solution {
+    fn rope(&self, knots: usize) -> Rope {
+        let mut rope = Rope::new(knots);
+        self.input.iter().for_each(|m| rope.apply(m));
+        rope
+    }
+
+    fn tail_positions(&self, knots: usize) -> usize {
+        let rope = self.rope(knots);
+        rope.visited(rope.tail()).len()
+    }
+
+    /// The rope drawn after every step, under a heading for each move, and
+    /// then everywhere the tail went. Every diagram covers the same area.
+    fn walkthrough(&self, knots: usize) -> String {
+        let bounds = self.rope(knots).bounds();
+        let mut rope = Rope::new(knots);
+        let mut out = vec![rope.render(bounds)];
+        for m in self.input.iter() {
+            out.push(format!("== {m} =="));
+            rope.apply_with(m, |rope| out.push(rope.render(bounds)));
+        }
+        out.push(rope.render_trail(rope.tail(), bounds));
+        out.join("\n\n")
+    }
+}
+
+/// The part 1 walkthrough, like the puzzle text's.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.walkthrough(PART1_KNOTS))
+    }
+}
+
 impl Solver for Solution {
     fn get_input(&self) -> &'static str {
         INPUT
@@ -88,41 +205,11 @@ impl Solver for Solution {
     }
 
     fn solve_part1(&self) -> String {
-        let mut head = Position(0, 0);
-        let mut tail = Position(0, 0);
-        let mut tail_pos = HashSet::new();
-        tail_pos.insert(tail.clone());
-
-        for m in self.input.iter() {
-            for _i in 0..m.amt {
-                head.move_by_dir(&m.dir);
-                tail.move_closer_to(&head);
-                tail_pos.insert(tail.clone());
-            }
-        }
-
-        tail_pos.len().to_string()
+        self.tail_positions(PART1_KNOTS).to_string()
     }
 
     fn solve_part2(&self) -> String {
-        let rope_len = 10;
-        let mut rope = vec![Position(0, 0); rope_len];
-        let mut tail_pos = HashSet::new();
-        tail_pos.insert(rope.last().unwrap().clone());
-
-        for m in self.input.iter() {
-            for _i in 0..m.amt {
-                rope.first_mut().unwrap().move_by_dir(&m.dir);
-                for j in 1..rope_len {
-                    let prev = rope.get(j - 1).unwrap().clone();
-                    let cur = rope.get_mut(j).unwrap();
-                    cur.move_closer_to(&prev);
-                }
-                tail_pos.insert(rope.last().unwrap().clone());
-            }
-        }
-
-        tail_pos.len().to_string()
+        self.tail_positions(PART2_KNOTS).to_string()
     }
 }
 
@@ -168,6 +255,85 @@ U 20"
         let solution = solver.solve_part2();
         assert_eq!(solution, "36");
     }
+
+    fn rope_after(input: &str, knots: usize) -> Rope {
+        let mut solver = Solution::default();
+        solver.with_input(input);
+        solver.rope(knots)
+    }
+
+    #[test]
+    fn test_render_diagrams() {
+        let frame = (Position::new(0, -4), Position::new(5, 0));
+        let rope = rope_after(get_input1(), 2);
+        assert_eq!(rope.render(frame), "......\n......\n.TH...\n......\ns.....");
+        assert_eq!(
+            rope.render_trail(rope.tail(), frame),
+            "..##..\n...##.\n.####.\n....#.\ns###.."
+        );
+
+        let rope = rope_after("R 4", 10);
+        assert_eq!(rope.render(frame), "......\n......\n......\n......\n4321H.");
+
+        let rope = rope_after(get_input2(), 10);
+        let (min, max) = (Position::new(-11, -15), Position::new(14, 5));
+        let drawn = rope.render((min, max));
+        assert_eq!(drawn.lines().nth(9), Some("9........................."));
+        assert_eq!(drawn.lines().nth(15), Some("...........s.............."));
+        assert_eq!(
+            rope.render_trail(rope.tail(), (Position::new(-11, -6), Position::new(14, 5))),
+            "\
+#.........................
+#.............###.........
+#............#...#........
+.#..........#.....#.......
+..#..........#.....#......
+...#........#.......#.....
+....#......s.........#....
+.....#..............#.....
+......#............#......
+.......#..........#.......
+........#........#........
+.........########........."
+        );
+        assert_eq!(rope.bounds(), (min, max));
+    }
+
+    #[test]
+    fn test_walkthrough() {
+        let mut solver = Solution::default();
+        solver.with_input("R 2\nU 1");
+        assert_eq!(
+            solver.to_string(),
+            "\
+...
+H..
+
+== R 2 ==
+
+...
+TH.
+
+...
+sTH
+
+== U 1 ==
+
+..H
+sT.
+
+...
+s#."
+        );
+    }
+
+    #[test]
+    fn test_diagonal_moves() {
+        let rope = rope_after("UR 3\nL 1", 3);
+        assert_eq!(rope.render(rope.bounds()), "..H.\n..1.\n.2..\ns...");
+        assert_eq!(rope.visited(0).len(), 5);
+        assert_eq!(rope.visited(2).len(), 2);
+    }
 }
 
 const INPUT: &str = "R 2
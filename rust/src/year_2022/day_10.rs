@@ -1,8 +1,11 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
-use crate::solver::Solver;
+use crate::{
+    solver::Solver,
+    utils::cpu::{Device, Instruction, Machine, Stop},
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum Command {
     Noop,
     Addx(isize),
@@ -24,15 +27,103 @@ impl FromStr for Command {
     }
 }
 
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Command::Noop => write!(f, "noop"),
+            Command::Addx(amt) => write!(f, "addx {amt}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-struct CPUState {
-    register: isize,
-    add: Option<isize>,
+struct Registers {
+    x: isize,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Self { x: 1 }
+    }
+}
+
+impl Instruction for Command {
+    type State = Registers;
+
+    fn cycles(&self) -> usize {
+        match self {
+            Command::Noop => 1,
+            Command::Addx(_) => 2,
+        }
+    }
+
+    fn execute(&self, registers: &mut Registers) {
+        if let Command::Addx(amt) = self {
+            registers.x += amt;
+        }
+    }
+}
+
+/// A screen drawn one pixel per cycle, left to right and top to bottom,
+/// wrapping back to the top once it's full. A pixel is lit when the sprite,
+/// centred on `x`, covers it.
+#[derive(Debug, Clone)]
+struct Crt {
+    width: usize,
+    height: usize,
+    sprite_width: usize,
+    pixels: Vec<bool>,
+}
+
+impl Crt {
+    fn new(width: usize, height: usize, sprite_width: usize) -> Self {
+        assert!(width > 0 && height > 0, "a screen needs at least one pixel");
+        Self {
+            width,
+            height,
+            sprite_width,
+            pixels: vec![false; width * height],
+        }
+    }
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self::new(40, 6, 3)
+    }
+}
+
+impl Device<Registers> for Crt {
+    fn tick(&mut self, cycle: usize, registers: &Registers) {
+        let pixel = (cycle - 1) % self.pixels.len();
+        let column = (pixel % self.width) as isize;
+        let left = registers.x - (self.sprite_width as isize - 1) / 2;
+        self.pixels[pixel] = (left..left + self.sprite_width as isize).contains(&column);
+    }
+}
+
+impl Display for Crt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows = self
+            .pixels
+            .chunks(self.width)
+            .take(self.height)
+            .map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect())
+            .collect::<Vec<String>>();
+        write!(f, "{}", rows.join("\n"))
+    }
 }
 
 #[derive(Default)]
 pub struct Solution {
-    input: Vec<CPUState>,
+    program: Vec<Command>,
+    crt: Crt,
+}
+
+impl Solution {
+    fn machine(&self) -> Machine<Command> {
+        Machine::new(self.program.clone(), Registers::default())
+    }
 }
 
 impl Solver for Solution {
@@ -41,68 +132,40 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.input = input
-            .lines()
-            .map(|l| l.parse().unwrap())
-            .flat_map(|c| match c {
-                Command::Noop => vec![None],
-                Command::Addx(amt) => vec![None, Some(amt)],
-            })
-            .scan(
-                CPUState {
-                    register: 1,
-                    add: None,
-                },
-                |cpu, add| {
-                    // this add amount is from the previous cycle
-                    // so we do it here which is equivelent to doing
-                    // it at the end of the previous cycle
-                    if let Some(amt) = cpu.add {
-                        cpu.register += amt;
-                    }
-                    // queue up the next possible add
-                    cpu.add = add;
-                    Some(cpu.clone())
-                },
-            )
-            .collect();
+        self.program = input.lines().map(|l| l.parse().unwrap()).collect();
     }
 
     fn solve_part1(&self) -> String {
-        let signal_strength = self
-            .input
-            .iter()
-            .skip(19) // we skip 19 because the number of cycles starts at 1
-            .step_by(40)
-            .enumerate()
-            .fold(0_isize, |acc, (i, cpu)| {
-                acc + (((i as isize * 40) + 20) * cpu.register)
-            });
-
-        signal_strength.to_string()
+        // the signal is sampled during the 20th cycle and every 40 after it
+        let mut cpu = self.machine();
+        let mut strength = 0;
+        let mut next = 20;
+        loop {
+            cpu.break_at_cycle(next);
+            match cpu.run(&mut ()) {
+                Stop::Cycle(cycle) => strength += cycle as isize * cpu.state.x,
+                Stop::Halted => break,
+            }
+            next += 40;
+        }
+        strength.to_string()
     }
 
     fn solve_part2(&self) -> String {
-        let signal_strength = self
-            .input
-            .chunks(40)
-            .map(|chunk| {
-                chunk
-                    .iter()
-                    .enumerate()
-                    .map(|(i, cpu)| {
-                        let r = cpu.register;
-                        if i as isize >= r - 1 && i as isize <= r + 1 {
-                            "#"
-                        } else {
-                            "."
-                        }
-                    })
-                    .collect::<String>()
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
-        format!("\n\n{signal_strength}\n")
+        let mut crt = self.crt.clone();
+        self.machine().run(&mut crt);
+        format!("\n\n{crt}\n")
+    }
+}
+
+/// The program's run cycle by cycle, for seeing where `x` goes astray.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut cpu = self.machine().with_trace();
+        cpu.run(&mut ());
+        cpu.trace()
+            .iter()
+            .try_for_each(|line| writeln!(f, "{line}"))
     }
 }
 
@@ -283,6 +346,50 @@ noop"
 #######.......#######.......#######.....\n"
         );
     }
+
+    #[test]
+    fn test_trace() {
+        let mut solver = Solution::default();
+        solver.with_input("noop\naddx 3\naddx -5");
+        assert_eq!(
+            solver.to_string(),
+            "    1    0  noop             Registers { x: 1 }
+    2    1  addx 3           Registers { x: 1 }
+    3    1  addx 3           Registers { x: 1 }
+    4    2  addx -5          Registers { x: 4 }
+    5    2  addx -5          Registers { x: 4 }
+"
+        );
+
+        solver.with_input(get_input());
+        let mut cpu = solver.machine();
+        for (cycle, x) in [(20, 21), (60, 19), (220, 18)] {
+            cpu.break_at_cycle(cycle);
+            assert_eq!(cpu.run(&mut ()), Stop::Cycle(cycle));
+            assert_eq!(cpu.state.x, x);
+        }
+    }
+
+    #[test]
+    fn test_custom_crt() {
+        let mut solver = Solution {
+            crt: Crt::new(20, 2, 5),
+            ..Default::default()
+        };
+        // x is 1 for the first two cycles, 10 until the end of the first row,
+        // 10 again for the start of the second and then 2
+        let noops = vec!["noop"; 18].join("\n");
+        solver.with_input(&format!("addx 9\n{noops}\naddx -8\n{noops}"));
+        let solution = solver.solve_part2();
+        // the 5 wide sprite covers x - 2 to x + 2
+        assert_eq!(solution, "\n\n##......#####.......\n..###...............\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one pixel")]
+    fn test_empty_crt() {
+        Crt::new(0, 6, 3);
+    }
 }
 
 const INPUT: &str = "noop
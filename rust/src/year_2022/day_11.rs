@@ -1,117 +1,325 @@
-use std::{cell::RefCell, str::FromStr};
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use crate::{
+    solver::Solver,
+    utils::{
+        math::{lcm_all, BigInt},
+        parse::{self, ParseError},
+    },
+};
+
+/// A worry level. Plain `u64`s are exact but refuse to overflow, while
+/// [`BigInt`]s never run out of room but get slow once they're huge.
+trait Worry: Clone + Debug + Display {
+    fn from_u64(n: u64) -> Self;
+    fn apply(&self, op: Op, rhs: &Self) -> Self;
+    fn div(&self, d: u64) -> Self;
+    fn rem(&self, d: u64) -> Self;
+    fn is_multiple_of(&self, d: u64) -> bool;
+}
 
-use crate::solver::Solver;
+impl Worry for u64 {
+    fn from_u64(n: u64) -> Self {
+        n
+    }
 
-#[derive(Debug, Clone)]
+    fn apply(&self, op: Op, rhs: &Self) -> Self {
+        match op {
+            Op::Sum => self.checked_add(*rhs),
+            Op::Product => self.checked_mul(*rhs),
+        }
+        .expect("worry overflowed, keep it modular or use big ints")
+    }
+
+    fn div(&self, d: u64) -> Self {
+        self / d
+    }
+
+    fn rem(&self, d: u64) -> Self {
+        self % d
+    }
+
+    fn is_multiple_of(&self, d: u64) -> bool {
+        u64::is_multiple_of(*self, d)
+    }
+}
+
+impl Worry for BigInt {
+    fn from_u64(n: u64) -> Self {
+        BigInt::from(n as i128)
+    }
+
+    fn apply(&self, op: Op, rhs: &Self) -> Self {
+        match op {
+            Op::Sum => self + rhs,
+            Op::Product => self * rhs,
+        }
+    }
+
+    fn div(&self, d: u64) -> Self {
+        self / &Self::from_u64(d)
+    }
+
+    fn rem(&self, d: u64) -> Self {
+        self % &Self::from_u64(d)
+    }
+
+    fn is_multiple_of(&self, d: u64) -> bool {
+        Worry::rem(self, d).is_zero()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 enum Op {
     Sum,
     Product,
 }
 
-#[derive(Debug, Clone)]
-enum Target {
-    Me,
-    Amount(usize),
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Old,
+    Amount(u64),
+}
+
+impl Operand {
+    fn value<W: Worry>(&self, old: &W) -> W {
+        match self {
+            Operand::Old => old.clone(),
+            Operand::Amount(amt) => W::from_u64(*amt),
+        }
+    }
+}
+
+impl FromStr for Operand {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "old" {
+            Ok(Self::Old)
+        } else {
+            s.parse()
+                .map(Self::Amount)
+                .map_err(|_| ParseError::at(s, 0, format!("expected old or a number, not {s:?}")))
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct Operation {
+    left: Operand,
     op: Op,
-    target: Target,
+    right: Operand,
 }
 
 impl Operation {
-    fn apply(&self, old: usize) -> usize {
-        let amount = match self.target {
-            Target::Me => old,
-            Target::Amount(amt) => amt,
-        };
-
-        match self.op {
-            Op::Sum => old + amount,
-            Op::Product => old * amount,
-        }
+    fn apply<W: Worry>(&self, old: &W) -> W {
+        self.left.value(old).apply(self.op, &self.right.value(old))
     }
 }
 
 impl FromStr for Operation {
-    type Err = String;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (op, amt) = s.split_once(' ').unwrap();
-        let op = if op == "+" { Op::Sum } else { Op::Product };
-        let target = if amt == "old" {
-            Target::Me
-        } else {
-            Target::Amount(amt.parse().unwrap())
+        let captures = parse::pattern("{} {} {}", s)?;
+        let op = match captures.str(1) {
+            "+" => Op::Sum,
+            "*" => Op::Product,
+            op => return Err(ParseError::at(s, 0, format!("unknown operator {op:?}"))),
         };
-        Ok(Self { op, target })
+        Ok(Self {
+            left: captures.get(0)?,
+            op,
+            right: captures.get(2)?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
 struct Monkey {
     index: usize,
-    items: RefCell<Vec<usize>>,
+    items: Vec<u64>,
     operation: Operation,
-    test_divisible: usize,
+    test_divisible: u64,
     test_true_to_monkey: usize,
     test_false_to_monkey: usize,
 }
 
+impl Monkey {
+    fn target<W: Worry>(&self, worry: &W) -> usize {
+        if worry.is_multiple_of(self.test_divisible) {
+            self.test_true_to_monkey
+        } else {
+            self.test_false_to_monkey
+        }
+    }
+}
+
 impl FromStr for Monkey {
-    type Err = String;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.trim().lines().collect::<Vec<&str>>();
-
-        let index = lines[0]
-            .trim()
-            .trim_start_matches("Monkey ")
-            .trim_end_matches(':')
-            .parse()
-            .map_err(|_| "bad monkey index".to_string())?;
-
-        let items = lines[1]
-            .trim()
-            .trim_start_matches("Starting items: ")
-            .split(", ")
-            .map(|i| i.parse::<usize>().map_err(|_| "bad item".to_string()))
-            .collect::<Result<Vec<usize>, String>>()?;
-
-        let operation = lines[2]
-            .trim()
-            .trim_start_matches("Operation: new = old ")
-            .parse()
-            .unwrap();
-
-        let test_divisible = lines[3]
-            .trim()
-            .trim_start_matches("Test: divisible by ")
-            .parse()
-            .unwrap();
-
-        let test_true_to_monkey = lines[4]
-            .trim()
-            .trim_start_matches("If true: throw to monkey ")
-            .parse()
-            .unwrap();
-
-        let test_false_to_monkey = lines[5]
-            .trim()
-            .trim_start_matches("If false: throw to monkey ")
-            .parse()
-            .unwrap();
+        let captures = parse::pattern(
+            "Monkey {}:
+              Starting items: {}
+              Operation: new = {}
+              Test: divisible by {}
+                If true: throw to monkey {}
+                If false: throw to monkey {}",
+            s,
+        )?;
 
         Ok(Self {
-            index,
-            items: RefCell::new(items),
-            operation,
-            test_divisible,
-            test_true_to_monkey,
-            test_false_to_monkey,
+            index: captures.get(0)?,
+            items: parse::ints(captures.str(1)).collect(),
+            operation: captures.get(2)?,
+            test_divisible: captures.get(3)?,
+            test_true_to_monkey: captures.get(4)?,
+            test_false_to_monkey: captures.get(5)?,
         })
     }
 }
 
+#[derive(Debug, Clone)]
+struct Rules<W> {
+    rounds: usize,
+    /// What happens to worry after a monkey is done inspecting an item, with
+    /// `None` leaving it as it is.
+    relief: Option<fn(&W) -> W>,
+    /// Keeps worry modulo the lcm of every monkey's divisor, which leaves
+    /// all their tests giving the same answers. Only works when there's no
+    /// relief, as it needn't carry over to the remainders.
+    modular: bool,
+    log_throws: bool,
+}
+
+impl<W: Worry> Rules<W> {
+    fn part1() -> Self {
+        Self {
+            rounds: 20,
+            relief: Some(|w| w.div(3)),
+            modular: false,
+            log_throws: false,
+        }
+    }
+
+    fn part2() -> Self {
+        Self {
+            rounds: 10000,
+            relief: None,
+            modular: true,
+            log_throws: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Throw<W> {
+    round: usize,
+    from: usize,
+    to: usize,
+    /// The worry level before and after the inspection.
+    old: W,
+    new: W,
+}
+
+#[derive(Debug, Clone)]
+struct Report<W> {
+    /// How many items each monkey inspected in each round.
+    rounds: Vec<Vec<u64>>,
+    /// Who holds what once the last round is over.
+    items: Vec<Vec<W>>,
+    /// Every throw in order, when the rules asked for them.
+    throws: Vec<Throw<W>>,
+}
+
+impl<W> Report<W> {
+    /// How many items each monkey inspected over the first `rounds` rounds.
+    fn inspections_after(&self, rounds: usize) -> Vec<u64> {
+        self.rounds[..rounds]
+            .iter()
+            .fold(vec![0; self.items.len()], |mut totals, round| {
+                totals.iter_mut().zip(round).for_each(|(t, n)| *t += n);
+                totals
+            })
+    }
+
+    fn inspections(&self) -> Vec<u64> {
+        self.inspections_after(self.rounds.len())
+    }
+
+    /// The product of the two biggest inspection counts.
+    fn monkey_business(&self) -> u64 {
+        let mut inspections = self.inspections();
+        inspections.sort_unstable_by(|a, b| b.cmp(a));
+        inspections.iter().take(2).product()
+    }
+}
+
+fn simulate<W: Worry>(monkeys: &[Monkey], rules: &Rules<W>) -> Result<Report<W>, String> {
+    for (i, m) in monkeys.iter().enumerate() {
+        if m.index != i {
+            return Err(format!("monkey {} is listed in position {i}", m.index));
+        }
+        for to in [m.test_true_to_monkey, m.test_false_to_monkey] {
+            if to >= monkeys.len() || to == i {
+                return Err(format!("monkey {i} can't throw to monkey {to}"));
+            }
+        }
+        if m.test_divisible == 0 {
+            return Err(format!("monkey {i} tests for divisibility by 0"));
+        }
+    }
+    let modulus = match (rules.modular, rules.relief) {
+        (false, _) => None,
+        (true, None) => lcm_all(monkeys.iter().map(|m| m.test_divisible)),
+        (true, Some(_)) => return Err("relief can't be done modulo the divisors".to_string()),
+    };
+
+    let mut items = monkeys
+        .iter()
+        .map(|m| m.items.iter().map(|&i| W::from_u64(i)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut report = Report {
+        rounds: Vec::with_capacity(rules.rounds),
+        items: Vec::new(),
+        throws: Vec::new(),
+    };
+
+    for round in 1..=rules.rounds {
+        let mut inspected = vec![0; monkeys.len()];
+        for m in monkeys {
+            let held = std::mem::take(&mut items[m.index]);
+            inspected[m.index] += held.len() as u64;
+            for old in held {
+                let mut new = m.operation.apply(&old);
+                if let Some(relief) = rules.relief {
+                    new = relief(&new);
+                }
+                if let Some(modulus) = modulus {
+                    new = new.rem(modulus);
+                }
+
+                let to = m.target(&new);
+                if rules.log_throws {
+                    report.throws.push(Throw {
+                        round,
+                        from: m.index,
+                        to,
+                        old,
+                        new: new.clone(),
+                    });
+                }
+                items[to].push(new);
+            }
+        }
+        report.rounds.push(inspected);
+    }
+
+    report.items = items;
+    Ok(report)
+}
+
 #[derive(Default, Clone)]
 pub struct Solution {
     input: Vec<Monkey>,
@@ -123,75 +331,21 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.input = input
-            .split("\n\n")
-            .map(|c| c.parse::<Monkey>().unwrap())
-            .collect();
+        self.input = parse::blocks(input, |b| b.parse()).unwrap();
     }
 
     fn solve_part1(&self) -> String {
-        let monkeys = self.input.clone();
-        let mut monkey_business = vec![0; monkeys.len()];
-        let num_roundds = 20;
-        for _ in 0..num_roundds {
-            for m in monkeys.iter() {
-                for i in m.items.borrow_mut().drain(0..) {
-                    monkey_business[m.index] += 1;
-                    let worry = m.operation.apply(i) / 3;
-                    if worry % m.test_divisible == 0 {
-                        monkeys[m.test_true_to_monkey]
-                            .items
-                            .borrow_mut()
-                            .push(worry);
-                    } else {
-                        monkeys[m.test_false_to_monkey]
-                            .items
-                            .borrow_mut()
-                            .push(worry);
-                    }
-                }
-            }
-        }
-
-        monkey_business.sort();
-        monkey_business.reverse();
-        let solution = monkey_business[0] * monkey_business[1];
-        solution.to_string()
+        simulate::<u64>(&self.input, &Rules::part1())
+            .unwrap()
+            .monkey_business()
+            .to_string()
     }
 
     fn solve_part2(&self) -> String {
-        let monkeys = self.input.clone();
-
-        // get common denominator for all monkey divisors so we
-        // can use the modulo of this LCD to keep worry low
-        let common_denom: usize = monkeys.iter().map(|m| m.test_divisible).product();
-
-        let mut monkey_business = vec![0_u64; monkeys.len()];
-        let num_roundds = 10000;
-        for _ in 0..num_roundds {
-            for m in monkeys.iter() {
-                for i in m.items.borrow_mut().drain(0..) {
-                    monkey_business[m.index] += 1;
-                    let worry = m.operation.apply(i) % common_denom;
-                    if worry % m.test_divisible == 0 {
-                        monkeys[m.test_true_to_monkey]
-                            .items
-                            .borrow_mut()
-                            .push(worry);
-                    } else {
-                        monkeys[m.test_false_to_monkey]
-                            .items
-                            .borrow_mut()
-                            .push(worry);
-                    }
-                }
-            }
-        }
-
-        monkey_business.sort();
-        monkey_business.reverse();
-        let solution = monkey_business[0] * monkey_business[1];
-        solution.to_string()
+        simulate::<u64>(&self.input, &Rules::part2())
+            .unwrap()
+            .monkey_business()
+            .to_string()
     }
 }
 
@@ -245,6 +399,111 @@ Test: divisible by 17
         let solution = solver.solve_part2();
         assert_eq!(solution, "2713310158");
     }
+
+    fn monkeys() -> Vec<Monkey> {
+        parse::blocks(get_input(), |b| b.parse()).unwrap()
+    }
+
+    #[test]
+    fn test_walkthrough() {
+        let rules = Rules {
+            rounds: 1,
+            log_throws: true,
+            ..Rules::part1()
+        };
+        let report = simulate::<u64>(&monkeys(), &rules).unwrap();
+        let throw = |from, to, old, new| Throw {
+            round: 1,
+            from,
+            to,
+            old,
+            new,
+        };
+        assert_eq!(
+            report.throws[..2],
+            [throw(0, 3, 79, 500), throw(0, 3, 98, 620)]
+        );
+        assert_eq!(report.throws.len(), 10 + 4);
+        assert_eq!(
+            report.items,
+            vec![
+                vec![20, 23, 27, 26],
+                vec![2080, 25, 167, 207, 401, 1046],
+                vec![],
+                vec![]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_relief() {
+        let rules = Rules {
+            rounds: 1,
+            relief: Some(|w: &u64| w / 2),
+            log_throws: true,
+            ..Rules::part1()
+        };
+        let report = simulate(&monkeys(), &rules).unwrap();
+        // 79 * 19 = 1501, halved is 750, which isn't divisible by 23
+        assert_eq!(
+            report.throws[0],
+            Throw {
+                round: 1,
+                from: 0,
+                to: 3,
+                old: 79,
+                new: 750
+            }
+        );
+    }
+
+    #[test]
+    fn test_inspection_stats() {
+        let report = simulate::<u64>(&monkeys(), &Rules::part2()).unwrap();
+        assert_eq!(report.inspections_after(1), [2, 4, 3, 6]);
+        assert_eq!(report.inspections_after(20), [99, 97, 8, 103]);
+        assert_eq!(report.inspections_after(1000), [5204, 4792, 199, 5192]);
+        assert_eq!(report.inspections(), [52166, 47830, 1938, 52013]);
+    }
+
+    #[test]
+    fn test_big_ints() {
+        let big = simulate::<BigInt>(&monkeys(), &Rules::part1()).unwrap();
+        assert_eq!(big.monkey_business(), 10605);
+
+        // without relief or remainders the worry levels grow fast
+        let rules = Rules {
+            rounds: 20,
+            modular: false,
+            ..Rules::part2()
+        };
+        let big = simulate::<BigInt>(&monkeys(), &rules).unwrap();
+        let modular = simulate::<u64>(
+            &monkeys(),
+            &Rules {
+                rounds: 20,
+                ..Rules::part2()
+            },
+        )
+        .unwrap();
+        assert_eq!(big.rounds, modular.rounds);
+        // which is more than plain u64s could have held
+        let u64_max = BigInt::from(u64::MAX as i128);
+        assert!(big.items.iter().flatten().any(|w| *w > u64_max));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let rules = Rules {
+            modular: true,
+            ..Rules::part1()
+        };
+        assert!(simulate::<u64>(&monkeys(), &rules).is_err());
+
+        let mut monkeys = monkeys();
+        monkeys[1].test_false_to_monkey = 4;
+        assert!(simulate::<u64>(&monkeys, &Rules::part1()).is_err());
+    }
 }
 
 const INPUT: &str = "Monkey 0:
@@ -1,44 +1,27 @@
-use std::{
-    collections::{BinaryHeap, HashMap},
-    fmt::Display,
-    hash::Hash,
-};
+use std::fmt::Display;
 
-use crate::solver::Solver;
+use crate::{
+    solver::Solver,
+    utils::{grid::Grid, point::Point2, search},
+};
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position(i16, i16);
-
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
-struct Point {
-    position: Position,
-    height: u8,
-}
+type Position = Point2<i16>;
 
 #[derive(Default, Debug)]
-struct Grid(Vec<Vec<Point>>);
-
-impl Grid {
-    fn get(&self, pos: Position) -> Option<&Point> {
-        if pos.0 < 0 || pos.1 < 0 {
-            return None;
-        }
+struct HeightMap(Grid<u8>);
 
-        self.0.get(pos.1 as usize)?.get(pos.0 as usize)
+impl HeightMap {
+    fn get(&self, pos: Position) -> Option<&u8> {
+        self.0.get_signed(pos.y as isize, pos.x as isize)
     }
 
     fn neighbors(&self, cur: Position) -> Vec<Position> {
-        if let Some(Point {
-            position: Position(x, y),
-            height,
-        }) = self.get(cur)
-        {
-            [(*x, *y - 1), (*x, *y + 1), (*x - 1, *y), (*x + 1, *y)]
-                .iter()
-                .filter_map(|(x, y)| {
-                    self.get(Position(*x, *y))
-                        .filter(|p| p.height as i16 - *height as i16 <= 1)
-                        .map(|p| p.position)
+        if let Some(height) = self.get(cur) {
+            cur.neighbours_4()
+                .into_iter()
+                .filter(|p| {
+                    self.get(*p)
+                        .is_some_and(|h| *h as i16 - *height as i16 <= 1)
                 })
                 .collect()
         } else {
@@ -46,85 +29,25 @@ impl Grid {
         }
     }
 
-    fn heuristic(&self, a: Position, b: Position) -> i16 {
-        let Position(x1, y1) = a;
-        let Position(x2, y2) = b;
-        (x1 - x2).abs() + (y1 - y2).abs()
-    }
-
     fn astar(&self, start: &[Position], end: Position) -> Vec<Position> {
-        let mut frontier: BinaryHeap<WeightedPosition> =
-            BinaryHeap::from_iter(start.iter().map(|position| WeightedPosition {
-                position: *position,
-                weight: 0,
-            }));
-        let mut came_from: HashMap<Position, Option<Position>> =
-            start.iter().map(|start| (*start, None)).collect();
-        let mut cost_so_far: HashMap<Position, i16> =
-            start.iter().map(|start| (*start, 0i16)).collect();
-
-        while let Some(current) = frontier.pop() {
-            if current.position == end {
-                break;
-            }
-
-            for next in self.neighbors(current.position) {
-                let new_cost = cost_so_far.get(&current.position).unwrap().to_owned() + 1;
-                if !cost_so_far.contains_key(&next) || new_cost < *cost_so_far.get(&next).unwrap() {
-                    cost_so_far
-                        .entry(next)
-                        .and_modify(|e| *e = new_cost)
-                        .or_insert(new_cost);
-
-                    let priority = new_cost + self.heuristic(next, end);
-                    frontier.push(WeightedPosition {
-                        position: next,
-                        weight: priority,
-                    });
-                    came_from.insert(next, Some(current.position));
-                }
-            }
-        }
-
-        let mut path = Vec::new();
-        if !came_from.contains_key(&end) {
-            return path;
-        }
-
-        let mut current = end;
-        while !start.contains(&current) {
-            path.push(current);
-            if let Some(Some(pos)) = came_from.get(&current) {
-                current = *pos;
-            }
-        }
-
-        path.reverse();
-        path
-    }
-}
-
-#[derive(PartialEq, Eq)]
-struct WeightedPosition {
-    position: Position,
-    weight: i16,
-}
-
-impl Ord for WeightedPosition {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.weight.cmp(&other.weight).reverse()
-    }
-}
+        let search = search::astar(
+            start.iter().copied(),
+            |p| self.neighbors(*p).into_iter().map(|n| (n, 1)),
+            |p| p.manhattan(&end),
+            |p| *p == end,
+        );
 
-impl PartialOrd for WeightedPosition {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.weight.partial_cmp(&other.weight).map(|c| c.reverse())
+        // the path includes the starting position, which isn't a step
+        search
+            .goal_path()
+            .map(|path| path[1..].to_vec())
+            .unwrap_or_default()
     }
 }
 
 #[derive(Default, Debug)]
 pub struct Solution {
-    grid: Grid,
+    grid: HeightMap,
     start: Position,
     alt_starts: Vec<Position>,
     end: Position,
@@ -136,37 +59,24 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.grid = Grid(
-            input
-                .lines()
-                .enumerate()
-                .map(|(y, l)| {
-                    l.chars()
-                        .enumerate()
-                        .map(|(x, c)| {
-                            let mut p = Point {
-                                position: Position(x as i16, y as i16),
-                                height: c as u8,
-                            };
-                            if c == 'a' {
-                                self.alt_starts.push(p.position);
-                            }
-                            if c == 'S' {
-                                self.alt_starts.push(p.position);
-                                self.start = p.position;
-                                p.height = 0;
-                            } else if c == 'E' {
-                                self.end = p.position;
-                                p.height = 25;
-                            } else {
-                                p.height -= 97;
-                            }
-                            p
-                        })
-                        .collect()
-                })
-                .collect(),
-        );
+        self.grid = HeightMap(Grid::parse_with_pos(input, |(y, x), c| {
+            let position = Position::new(x as i16, y as i16);
+            if c == 'a' {
+                self.alt_starts.push(position);
+            }
+            match c {
+                'S' => {
+                    self.alt_starts.push(position);
+                    self.start = position;
+                    0
+                }
+                'E' => {
+                    self.end = position;
+                    25
+                }
+                _ => c as u8 - b'a',
+            }
+        }));
     }
 
     fn solve_part1(&self) -> String {
@@ -183,12 +93,12 @@ impl Solver for Solution {
 
 impl Display for Solution {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.grid.0.iter().for_each(|r| {
-            r.iter().for_each(|p| {
-                write!(f, " {:0>2} ", p.height).unwrap();
-            });
-            writeln!(f).unwrap();
-        });
+        for row in self.grid.0.rows() {
+            for height in row {
+                write!(f, " {height:0>2} ")?;
+            }
+            writeln!(f)?;
+        }
 
         Ok(())
     }
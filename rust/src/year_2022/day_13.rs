@@ -1,120 +1,201 @@
-use crate::solver::Solver;
-use std::{cmp::Ordering, vec::Drain};
-
-#[derive(PartialEq, Eq)]
-enum Token {
-    Start,
-    End,
-    Value(u8),
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use crate::{
+    solver::Solver,
+    utils::parse::{self, ParseError},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Packet {
+    List(Vec<Packet>),
+    Value(u64),
 }
 
-struct PacketTokensIter {
-    packet: Vec<char>,
+/// A recursive descent parser over packet text, which is a subset of JSON:
+/// non-negative integers and lists of packets, with optional whitespace.
+struct PacketParser<'a> {
+    input: &'a str,
     pos: usize,
 }
 
-impl From<&str> for PacketTokensIter {
-    fn from(s: &str) -> Self {
-        PacketTokensIter {
-            packet: s.replace("10", "a").chars().collect(),
-            pos: 0,
+impl PacketParser<'_> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::at(self.input, self.pos, message)
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        while self
+            .input
+            .as_bytes()
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn packet(&mut self) -> Result<Packet, ParseError> {
+        match self.peek() {
+            Some(b'[') => self.list(),
+            Some(c) if c.is_ascii_digit() => self.value(),
+            Some(c) => Err(self.error(format!("expected a number or '[', found {:?}", c as char))),
+            None => Err(self.error("expected a number or '[', found the end")),
         }
     }
-}
 
-impl Iterator for PacketTokensIter {
-    type Item = Token;
-    fn next(&mut self) -> Option<Self::Item> {
-        let next = self.packet.get(self.pos)?;
+    fn list(&mut self) -> Result<Packet, ParseError> {
+        // skip the '['
         self.pos += 1;
-        match next {
-            ',' => self.next(),
-            '[' => Some(Token::Start),
-            ']' => Some(Token::End),
-            _ => {
-                let amt = next.to_digit(11)? as u8;
-                Some(Token::Value(amt))
+        let mut packets = Vec::new();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Packet::List(packets));
+        }
+
+        loop {
+            packets.push(self.packet()?);
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Packet::List(packets));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
             }
         }
     }
+
+    fn value(&mut self) -> Result<Packet, ParseError> {
+        let start = self.pos;
+        let digits = self.input[start..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        let value = self.input[start..start + digits]
+            .parse()
+            .map_err(|_| self.error("number too large"))?;
+        self.pos += digits;
+        Ok(Packet::Value(value))
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-enum Packet {
-    List(Vec<Packet>),
-    Value(u8),
+impl FromStr for Packet {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = PacketParser { input: s, pos: 0 };
+        let packet = parser.packet()?;
+        match parser.peek() {
+            None => Ok(packet),
+            Some(_) => Err(parser.error("unexpected text after the packet")),
+        }
+    }
 }
 
-fn create_packet(tokens: &mut Drain<Token>) -> Packet {
-    let mut packets: Vec<Packet> = Vec::new();
-    while let Some(next) = tokens.next() {
-        match next {
-            Token::Start => packets.push(create_packet(tokens)),
-            Token::Value(amt) => packets.push(Packet::Value(amt)),
-            Token::End => return Packet::List(packets),
-        };
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Value(value) => write!(f, "{value}"),
+            Packet::List(packets) => {
+                write!(f, "[")?;
+                for (i, packet) in packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{packet}")?;
+                }
+                write!(f, "]")
+            }
+        }
     }
+}
 
-    Packet::List(packets)
+impl Packet {
+    fn as_list(&self) -> &[Packet] {
+        match self {
+            Packet::List(packets) => packets,
+            // a lone value compares as a list holding just it
+            Packet::Value(_) => std::slice::from_ref(self),
+        }
+    }
+
+    /// `[[n]]`, the shape of the divider packets.
+    fn divider(n: u64) -> Self {
+        Packet::List(vec![Packet::List(vec![Packet::Value(n)])])
+    }
 }
 
-impl From<&str> for Packet {
-    fn from(s: &str) -> Self {
-        let p: PacketTokensIter = s.into();
-        let mut tokens = p.collect::<Vec<Token>>();
-        let mut tokens = tokens.drain(0..);
-        tokens.next();
-        create_packet(&mut tokens)
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Value(left), Packet::Value(right)) => left.cmp(right),
+            // slices compare item by item, then by length
+            _ => self.as_list().cmp(other.as_list()),
+        }
     }
 }
 
 impl PartialOrd for Packet {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let ord = match (self, other) {
-            (Packet::List(left), Packet::List(right)) => {
-                let mut left = left.iter().peekable();
-                let mut right = right.iter().peekable();
-
-                let mut ord = Ordering::Equal;
-                while left.peek().is_some() || right.peek().is_some() {
-                    ord = match (left.next(), right.next()) {
-                        (Some(l), Some(r)) => l.partial_cmp(r).unwrap(),
-                        (Some(_), None) => Ordering::Greater,
-                        (None, Some(_)) => Ordering::Less,
-                        (None, None) => Ordering::Equal,
-                    };
-
-                    if ord != Ordering::Equal {
-                        break;
-                    }
-                }
+        Some(self.cmp(other))
+    }
+}
 
-                ord
-            }
-            (Packet::Value(left), Packet::Value(right)) => left.cmp(right),
-            (Packet::List(_), Packet::Value(right)) => {
-                let list = vec![Packet::Value(*right)];
-                self.partial_cmp(&Packet::List(list)).unwrap()
-            }
-            (Packet::Value(left), Packet::List(_)) => {
-                let list = vec![Packet::Value(*left)];
-                Packet::List(list).partial_cmp(other).unwrap()
-            }
-        };
+/// The product of the 1-based positions the dividers would end up at if they
+/// were sorted in with the packets, found by sorting everything.
+fn decoder_key_sorted<'a>(
+    packets: impl IntoIterator<Item = &'a Packet>,
+    dividers: &'a [Packet],
+) -> usize {
+    let mut sorted = packets.into_iter().chain(dividers).collect::<Vec<_>>();
+    sorted.sort();
+
+    sorted
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| dividers.contains(p))
+        .map(|(i, _)| i + 1)
+        .product()
+}
 
-        Some(ord)
+/// The same as `decoder_key_sorted`, but only counting how many packets sort
+/// before each divider, so the packets can be streamed past without ever
+/// being collected.
+fn decoder_key<'a>(packets: impl IntoIterator<Item = &'a Packet>, dividers: &[Packet]) -> usize {
+    let mut before = dividers
+        .iter()
+        .map(|d| dividers.iter().filter(|other| *other < d).count())
+        .collect::<Vec<usize>>();
+    for packet in packets {
+        for (count, divider) in before.iter_mut().zip(dividers) {
+            if packet < divider {
+                *count += 1;
+            }
+        }
     }
+
+    before.iter().map(|count| count + 1).product()
 }
 
-impl Ord for Packet {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
-    }
+/// One blank-line separated block of the input, which has to hold exactly
+/// two packets.
+fn parse_pair(block: &str) -> Result<(Packet, Packet), ParseError> {
+    let packets = parse::lines(block, |l| l.parse())?;
+    let count = packets.len();
+    <[Packet; 2]>::try_from(packets)
+        .map(|[left, right]| (left, right))
+        .map_err(|_| ParseError::at(block, 0, format!("expected 2 packets, found {count}")))
 }
 
 #[derive(Default, Debug)]
 pub struct Solution {
-    input: Vec<Packet>,
+    input: Vec<(Packet, Packet)>,
+}
+
+impl Solution {
+    fn packets(&self) -> impl Iterator<Item = &Packet> {
+        self.input.iter().flat_map(|(left, right)| [left, right])
+    }
 }
 
 impl Solver for Solution {
@@ -123,43 +204,24 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.input = input
-            .lines()
-            .filter(|l| !l.is_empty())
-            .map(|l| l.into())
-            .collect();
+        self.input = parse::blocks(input, parse_pair).unwrap();
     }
 
     fn solve_part1(&self) -> String {
         self.input
-            .chunks(2)
+            .iter()
             .enumerate()
-            .fold(0, |acc, (i, pair)| {
-                let left = pair.get(0).unwrap();
-                let right = pair.get(1).unwrap();
-                if left < right {
-                    acc + i + 1
-                } else {
-                    acc
-                }
-            })
+            .filter(|(_, (left, right))| left < right)
+            .map(|(i, _)| i + 1)
+            .sum::<usize>()
             .to_string()
     }
 
     fn solve_part2(&self) -> String {
-        let mut packets = self.input.clone();
-        let divider1 = Packet::List(vec![Packet::List(vec![Packet::Value(2)])]);
-        let divider2 = Packet::List(vec![Packet::List(vec![Packet::Value(6)])]);
-        packets.push(divider1.clone());
-        packets.push(divider2.clone());
-        packets.sort();
-
-        packets
-            .iter()
-            .enumerate()
-            .filter(|p| p.1 == &divider1 || p.1 == &divider2)
-            .fold(1, |acc, (i, _)| acc * (i + 1))
-            .to_string()
+        let dividers = [Packet::divider(2), Packet::divider(6)];
+        let key = decoder_key(self.packets(), &dividers);
+        debug_assert_eq!(key, decoder_key_sorted(self.packets(), &dividers));
+        key.to_string()
     }
 }
 
@@ -210,6 +272,49 @@ mod test {
         assert_eq!(solution, "140");
     }
 
+    #[test]
+    fn test_packet_round_trip() {
+        for text in [
+            "[]",
+            "[[[]]]",
+            "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+            "[10,[255,1000000]]",
+        ] {
+            assert_eq!(text.parse::<Packet>().unwrap().to_string(), text);
+        }
+        assert_eq!(
+            " [ 1 , [ ] ] ".parse::<Packet>().unwrap().to_string(),
+            "[1,[]]"
+        );
+        assert!("[10] < [9]".parse::<Packet>().is_err());
+        assert!("[10]".parse::<Packet>().unwrap() > "[9]".parse::<Packet>().unwrap());
+
+        let err = "[1,[2,x]]".parse::<Packet>().unwrap_err();
+        assert_eq!((err.line, err.column), (1, 7));
+        let err = "[1,2".parse::<Packet>().unwrap_err();
+        assert_eq!(err.column, 5);
+        assert!("[99999999999999999999999]".parse::<Packet>().is_err());
+    }
+
+    #[test]
+    fn test_unpaired_packets() {
+        let err = parse::blocks("[1]\n[2]\n\n[3]", parse_pair).unwrap_err();
+        assert_eq!(err.line, 4);
+        assert_eq!(err.message, "expected 2 packets, found 1");
+        assert!(parse::blocks("[1]\n[2]\n[3]", parse_pair).is_err());
+    }
+
+    #[test]
+    fn test_decoder_key() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let dividers = [Packet::divider(2), Packet::divider(6), Packet::divider(11)];
+        assert_eq!(
+            decoder_key(solver.packets(), &dividers),
+            decoder_key_sorted(solver.packets(), &dividers)
+        );
+    }
+
     #[test]
     fn test_solution_part1() {
         let mut solver = Solution::default();
@@ -1,38 +1,13 @@
 use std::{
     collections::HashSet,
     i16::MAX,
-    ops::{Add, AddAssign},
-    str::FromStr,
     // thread,
     // time::Duration,
 };
 
-use crate::solver::Solver;
+use crate::{solver::Solver, utils::point::Point2};
 
-#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Copy)]
-struct Point(i16, i16);
-
-impl FromStr for Point {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s.split_once(',').unwrap();
-        Ok(Self(x.parse().unwrap(), y.parse().unwrap()))
-    }
-}
-
-impl Add for Point {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-impl AddAssign for Point {
-    fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
-    }
-}
+type Point = Point2<i16>;
 
 #[derive(Debug)]
 struct Line(Vec<Point>);
@@ -58,7 +33,7 @@ impl Solver for Solution {
             let lines: Vec<Point> = l
                 .split(" -> ")
                 .map(|c| c.parse().unwrap())
-                .inspect(|Point(x, y)| {
+                .inspect(|Point { x, y }| {
                     self.min_x = self.min_x.min(*x);
                     self.min_y = self.min_y.min(*y);
                     self.max_y = self.max_y.max(*y);
@@ -66,16 +41,16 @@ impl Solver for Solution {
                 })
                 .collect();
             lines.windows(2).for_each(|p| {
-                let Point(x1, y1) = p[0];
-                let Point(x2, y2) = p[1];
+                let Point { x: x1, y: y1 } = p[0];
+                let Point { x: x2, y: y2 } = p[1];
 
                 if x1 == x2 {
                     for y in y1.min(y2)..=y1.max(y2) {
-                        self.rock.insert(Point(x1, y));
+                        self.rock.insert(Point::new(x1, y));
                     }
                 } else if y1 == y2 {
                     for x in x1.min(x2)..=x1.max(x2) {
-                        self.rock.insert(Point(x, y1));
+                        self.rock.insert(Point::new(x, y1));
                     }
                 } else {
                     panic!("WARN: some lines are not purely vertical or horizontal")
@@ -89,9 +64,9 @@ impl Solver for Solution {
         let mut sand_count = 0;
 
         let mut sands: HashSet<Point> = HashSet::new();
-        let dirs = [Point(0, 1), Point(-1, 1), Point(1, 1)];
+        let dirs = [Point::new(0, 1), Point::new(-1, 1), Point::new(1, 1)];
         while !in_the_abyss {
-            let mut sand = Point(500, 0);
+            let mut sand = Point::new(500, 0);
             let mut is_done = false;
             while !is_done {
                 is_done = true;
@@ -106,7 +81,7 @@ impl Solver for Solution {
                 if is_done {
                     sand_count += 1;
                     sands.insert(sand);
-                } else if sand.1 > self.max_y {
+                } else if sand.y > self.max_y {
                     in_the_abyss = true;
                     break;
                 }
@@ -122,13 +97,13 @@ impl Solver for Solution {
         // self.clear_terminal();
         // self.draw_rocks();
         let mut sands: HashSet<Point> = HashSet::new();
-        let dirs = [Point(0, 1), Point(-1, 1), Point(1, 1)];
+        let dirs = [Point::new(0, 1), Point::new(-1, 1), Point::new(1, 1)];
         while !standing_on_top {
-            let mut sand = Point(500, 0);
+            let mut sand = Point::new(500, 0);
             let mut is_done = false;
             while !is_done {
                 is_done = true;
-                if sand.1 != self.max_y + 1 {
+                if sand.y != self.max_y + 1 {
                     for dir in dirs {
                         let target = sand + dir;
                         if !self.rock.contains(&target) && !sands.contains(&target) {
@@ -142,7 +117,7 @@ impl Solver for Solution {
                 if is_done {
                     sand_count += 1;
                     sands.insert(sand);
-                    if sand.1 == 0 {
+                    if sand.y == 0 {
                         standing_on_top = true;
                         break;
                     }
@@ -217,13 +192,13 @@ mod test {
 //     }
 
 //     fn draw_sand(&self, sand: &HashSet<Point>) {
-//         sand.iter().for_each(|Point(x, y)| {
+//         sand.iter().for_each(|Point { x, y }| {
 //             print!("\x1b[{};{}H", *y + Y_OFFSET, *x + X_OFFSET);
 //             print!("o");
 //         });
 //     }
 //     fn draw_rocks(&self) {
-//         self.rock.iter().for_each(|Point(x, y)| {
+//         self.rock.iter().for_each(|Point { x, y }| {
 //             print!("\x1b[{};{}H", *y + Y_OFFSET, *x + X_OFFSET);
 //             print!("x");
 //         });
@@ -236,9 +211,9 @@ mod test {
 
 //     fn animate(&self, sand: &Point, prev: &Point) {
 //         thread::sleep(Duration::from_millis(16));
-//         print!("\x1b[{};{}H", sand.1 + Y_OFFSET, sand.0 + X_OFFSET);
+//         print!("\x1b[{};{}H", sand.y + Y_OFFSET, sand.x + X_OFFSET);
 //         print!("o");
-//         print!("\x1b[{};{}H", prev.1 + Y_OFFSET, prev.0 + X_OFFSET);
+//         print!("\x1b[{};{}H", prev.y + Y_OFFSET, prev.x + X_OFFSET);
 //         print!(" ");
 //     }
 // }
@@ -1,9 +1,16 @@
-use std::{collections::HashSet, str::FromStr, sync::mpsc, thread::available_parallelism};
+use std::{collections::HashSet, str::FromStr};
 
-use crate::{solver::Solver, utils::ThreadPool};
+use crate::{
+    solver::Solver,
+    utils::{
+        parallel::par_find_any,
+        parse::{self, parse, ParseError},
+        point::Point2,
+        ranges::RangeSet,
+    },
+};
 
-#[derive(Debug, Hash, Clone)]
-struct Position(i64, i64);
+type Position = Point2<i64>;
 
 #[derive(Debug, Clone)]
 struct Beacon {
@@ -21,72 +28,140 @@ struct Sensor {
 }
 
 impl FromStr for Sensor {
-    type Err = String;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (sensor, beacon) = s
-            .trim_start_matches("Sensor at x=")
-            .split_once(": closest beacon is at x=")
-            .unwrap();
-        let (sx, sy) = sensor.split_once(", y=").unwrap();
-        let (bx, by) = beacon.split_once(", y=").unwrap();
-        let bx: i64 = bx.parse().unwrap();
-        let by: i64 = by.parse().unwrap();
-        let sx: i64 = sx.parse().unwrap();
-        let sy: i64 = sy.parse().unwrap();
-
-        let dist = (sx - bx).abs() + (sy - by).abs();
-
+        let (sx, sy, bx, by) = parse!("Sensor at x={}, y={}: closest beacon is at x={}, y={}", s)?;
+        let pos = Position::new(sx, sy);
         let beacon = Beacon {
-            pos: Position(bx, by),
+            pos: Position::new(bx, by),
         };
 
+        let dist = pos.manhattan(&beacon.pos);
+
         let sensor = Sensor {
-            pos: Position(sx, sy),
-            min_x: sx - dist,
-            max_x: sx + dist,
-            min_y: sy - dist,
-            max_y: sy + dist,
+            pos,
+            min_x: pos.x - dist,
+            max_x: pos.x + dist,
+            min_y: pos.y - dist,
+            max_y: pos.y + dist,
             beacon,
         };
         Ok(sensor)
     }
 }
 
-#[derive(Default, Debug)]
+impl Sensor {
+    /// How far the sensor can see, which is as far as its beacon.
+    fn radius(&self) -> i64 {
+        self.max_x - self.pos.x
+    }
+
+    fn covers(&self, p: &Position) -> bool {
+        self.pos.manhattan(p) <= self.radius()
+    }
+}
+
+#[derive(Debug)]
 pub struct Solution {
     sensors: Vec<Sensor>,
     part1_row: i64,
-    part2_max: usize,
+    // the distress beacon is somewhere in 0..=part2_max on both axes
+    part2_max: i64,
+}
+
+impl Default for Solution {
+    fn default() -> Self {
+        Self {
+            sensors: Vec::new(),
+            part1_row: 2000000,
+            part2_max: 4000000,
+        }
+    }
+}
+
+fn tuning_frequency(p: &Position) -> i64 {
+    p.x * 4000000 + p.y
 }
 
 impl Solution {
-    fn get_ranges(&self, y: i64) -> Vec<(i64, i64)> {
-        get_ranges_between(&self.sensors, y, i64::MIN, i64::MAX)
+    fn get_ranges(&self, y: i64) -> RangeSet<i64> {
+        get_ranges_between(&self.sensors, y, i64::MIN, i64::MAX - 1)
+    }
+
+    fn in_search_area(&self, p: &Position) -> bool {
+        (0..=self.part2_max).contains(&p.x) && (0..=self.part2_max).contains(&p.y)
+    }
+
+    /// Finds the distress beacon by working in coordinates rotated 45
+    /// degrees, u = x + y and v = x - y, where every sensor's diamond becomes
+    /// a square. The only uncovered spot has to sit just outside the edges of
+    /// the sensors around it, so it's on one of the lines running one step
+    /// past each edge, and usually where a u line crosses a v line. Near the
+    /// edge of the search area it could instead be where a line meets the
+    /// border, or in a corner.
+    fn find_beacon(&self) -> Option<Position> {
+        let (mut us, mut vs) = (Vec::new(), Vec::new());
+        for s in self.sensors.iter() {
+            let reach = s.radius() + 1;
+            let (u, v) = (s.pos.x + s.pos.y, s.pos.x - s.pos.y);
+            us.extend([u - reach, u + reach]);
+            vs.extend([v - reach, v + reach]);
+        }
+
+        let crossings = us.iter().flat_map(|u| {
+            vs.iter()
+                .filter(move |v| (u + *v).rem_euclid(2) == 0)
+                .map(move |v| Position::new((u + v) / 2, (u - v) / 2))
+        });
+        let max = self.part2_max;
+        let on_border = us
+            .iter()
+            .flat_map(|u| {
+                [0, max].into_iter().flat_map(move |edge| {
+                    [Position::new(edge, u - edge), Position::new(u - edge, edge)]
+                })
+            })
+            .chain(vs.iter().flat_map(|v| {
+                [0, max].into_iter().flat_map(move |edge| {
+                    [Position::new(edge, edge - v), Position::new(v + edge, edge)]
+                })
+            }));
+        let corners = [0, max]
+            .into_iter()
+            .flat_map(|x| [0, max].into_iter().map(move |y| Position::new(x, y)));
+
+        crossings
+            .chain(on_border)
+            .chain(corners)
+            .filter(|p| self.in_search_area(p))
+            .find(|p| !self.sensors.iter().any(|s| s.covers(p)))
+    }
+
+    /// Finds the distress beacon by checking every row of the search area for
+    /// a gap between the sensors' ranges. Far slower than `find_beacon`, but
+    /// straightforward enough to check it against, and it can't miss a gap.
+    fn find_beacon_by_rows(&self) -> Option<Position> {
+        par_find_any(0..self.part2_max as usize + 1, |y| {
+            let y = y as i64;
+            let gap = get_ranges_between(&self.sensors, y, 0, self.part2_max)
+                .gaps()
+                .next()?;
+            Some(Position::new(gap.start, y))
+        })
     }
 }
 
-fn get_ranges_between(sensors: &[Sensor], y: i64, min_x: i64, max_x: i64) -> Vec<(i64, i64)> {
-    let mut ranges = sensors
+/// The x positions on row `y`, between `min_x` and `max_x` inclusive, that
+/// fall within range of a sensor.
+fn get_ranges_between(sensors: &[Sensor], y: i64, min_x: i64, max_x: i64) -> RangeSet<i64> {
+    sensors
         .iter()
         .filter(|s| (s.min_y..=s.max_y).contains(&y))
         .map(|s| {
-            let offset = (y - s.pos.1).abs();
-            (min_x.max(s.min_x + offset), max_x.min(s.max_x - offset))
+            let offset = (y - s.pos.y).abs();
+            min_x.max(s.min_x + offset)..=max_x.min(s.max_x - offset)
         })
-        .collect::<Vec<(i64, i64)>>();
-
-    ranges.sort_by_key(|r| r.0);
-    let first = *ranges.first().unwrap();
-    let merged_ranges = ranges.iter().skip(1).fold(vec![first], |mut acc, r| {
-        let last = acc.last_mut().unwrap();
-        if r.0 <= last.1 {
-            last.1 = last.1.max(r.1);
-        } else {
-            acc.push(*r);
-        }
-        acc
-    });
-    merged_ranges
+        .collect()
 }
 
 impl Solver for Solution {
@@ -95,9 +170,7 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.part1_row = input.lines().next().unwrap().parse().unwrap();
-        self.part2_max = self.part1_row as usize * 2;
-        self.sensors = input.lines().skip(1).map(|l| l.parse().unwrap()).collect();
+        self.sensors = parse::lines(input, |l| l.parse()).unwrap();
     }
 
     fn solve_part1(&self) -> String {
@@ -107,63 +180,21 @@ impl Solver for Solution {
         let beacons = self
             .sensors
             .iter()
-            .filter(|s| {
-                s.beacon.pos.1 == y
-                    && merged_ranges
-                        .iter()
-                        .filter(|(r1, r2)| (r1..=r2).contains(&&s.beacon.pos.0))
-                        .count()
-                        > 0
-            })
-            .map(|s| s.beacon.pos.0)
+            .filter(|s| s.beacon.pos.y == y && merged_ranges.contains(&s.beacon.pos.x))
+            .map(|s| s.beacon.pos.x)
             .collect::<HashSet<i64>>()
             .len();
 
-        let count = merged_ranges
-            .iter()
-            .fold(0, |acc, (r1, r2)| acc + 1 + r2 - r1);
-
-        (count - beacons as i64).to_string()
+        (merged_ranges.len() - beacons as i64).to_string()
     }
 
     fn solve_part2(&self) -> String {
-        let max_workers = available_parallelism().unwrap().get();
-
-        #[cfg(debug_assertions)]
-        println!("using {max_workers} worker threads");
-
-        let (res_tx, res_rx) = mpsc::channel::<String>();
-
-        let pool = ThreadPool::new(max_workers);
-
-        let max_range = self.part2_max as i64;
-
-        for i in 0..max_workers {
-            let size = self.part2_max / max_workers;
-            let sensors = self.sensors.clone();
-            let res_tx = res_tx.clone();
-            pool.execute(move || {
-                for y in i * size..(i + 1) * size {
-                    let ranges = get_ranges_between(&sensors, y as i64, 0, max_range);
-                    if ranges.len() == 2 {
-                        res_tx
-                            .send((((ranges[0].1 + 1) * 4000000) + y as i64).to_string())
-                            .unwrap();
-                        drop(res_tx);
-                        break;
-                    }
-                }
-            });
-        }
-
-        drop(pool);
-        drop(res_tx);
-
-        if let Ok(r) = res_rx.recv() {
-            r
-        } else {
-            "failed to find!".to_string()
-        }
+        // the geometric search only tries spots right on the edge of two
+        // sensors' ranges, so fall back to scanning every row if it misses
+        let beacon = self.find_beacon().or_else(|| self.find_beacon_by_rows());
+        beacon.map_or("failed to find!".to_string(), |p| {
+            tuning_frequency(&p).to_string()
+        })
     }
 }
 
@@ -173,7 +204,6 @@ mod test {
 
     fn get_example_input<'a>() -> &'a str {
         "\
-10
 Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 Sensor at x=9, y=16: closest beacon is at x=10, y=16
 Sensor at x=13, y=2: closest beacon is at x=15, y=3
@@ -192,7 +222,11 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3"
 
     #[test]
     fn test_solution_example1() {
-        let mut solver = Solution::default();
+        let mut solver = Solution {
+            part1_row: 10,
+            part2_max: 20,
+            ..Default::default()
+        };
         solver.with_input(get_example_input());
         let solution = solver.solve_part1();
         assert_eq!(solution, "26");
@@ -200,12 +234,27 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3"
 
     #[test]
     fn test_solution_example2() {
-        let mut solver = Solution::default();
+        let mut solver = Solution {
+            part1_row: 10,
+            part2_max: 20,
+            ..Default::default()
+        };
         solver.with_input(get_example_input());
         let solution = solver.solve_part2();
         assert_eq!(solution, "56000011");
     }
 
+    #[test]
+    fn test_find_beacon() {
+        let mut solver = Solution {
+            part2_max: 20,
+            ..Default::default()
+        };
+        solver.with_input(get_example_input());
+        assert_eq!(solver.find_beacon(), Some(Position::new(14, 11)));
+        assert_eq!(solver.find_beacon(), solver.find_beacon_by_rows());
+    }
+
     #[test]
     fn test_solution_part1() {
         let mut solver = Solution::default();
@@ -224,7 +273,6 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3"
 }
 
 const INPUT: &str = "\
-2000000
 Sensor at x=1384790, y=3850432: closest beacon is at x=2674241, y=4192888
 Sensor at x=2825953, y=288046: closest beacon is at x=2154954, y=-342775
 Sensor at x=3553843, y=2822363: closest beacon is at x=3444765, y=2347460
@@ -1,125 +1,258 @@
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    solver::Solver,
+    utils::{
+        bitset::BitSet,
+        parse::{self, parse, ParseError},
+        search,
+    },
 };
 
-use crate::solver::Solver;
+/// Which valves are open, by their index among the valves worth opening.
+type ValveSet = BitSet<2>;
+
+/// Past this many working valves, tables over every subset of them get too
+/// big and teams are put together by a pruned search instead.
+const DENSE_LIMIT: usize = 20;
 
 #[derive(Default, Debug, Clone)]
 struct Valve {
-    bit_mask: u32,
     key: String,
     flow_rate: usize,
-    neighbors: Vec<(usize, String)>,
+    neighbors: Vec<String>,
 }
 
 impl FromStr for Valve {
-    type Err = String;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (key, rest) = s
-            .trim_start_matches("Valve ")
-            .split_once(" has flow rate=")
-            .unwrap();
-        let (flow_rate, neighbors) = rest.split_once(';').unwrap();
-        let neighbors = neighbors
-            .trim_start_matches(|c: char| c.is_ascii_lowercase() || c.is_ascii_whitespace());
+        // the tunnel list reads "tunnel leads to valve" when there's only one
+        let (key, flow_rate, tunnels): (String, usize, String) =
+            parse!("Valve {} has flow rate={}; {}", s)?;
+        let neighbors =
+            tunnels.trim_start_matches(|c: char| c.is_ascii_lowercase() || c.is_ascii_whitespace());
         Ok(Self {
-            bit_mask: 0,
-            key: key.to_string(),
-            flow_rate: flow_rate.parse().unwrap(),
-            neighbors: neighbors.split(", ").map(|l| (1, l.to_string())).collect(),
+            key,
+            flow_rate,
+            neighbors: neighbors.split(", ").map(|l| l.to_string()).collect(),
         })
     }
 }
 
-struct ValveNetworkBFSIter<'a> {
-    network: &'a ValveNetwork,
-    visited: HashSet<String>,
-    to_visit: Vec<(usize, String)>,
+/// The valves worth opening, interned as indices into `flow_rates`, plus
+/// where everyone starts from.
+#[derive(Default, Debug, Clone)]
+struct ValveNetwork {
+    flow_rates: Vec<usize>,
+    // minutes to walk between any two of the valves worth opening, with the
+    // start tacked on the end if it isn't one of them
+    distances: Vec<Vec<usize>>,
+    start: usize,
 }
 
-impl<'a> ValveNetworkBFSIter<'a> {
-    fn new(network: &'a ValveNetwork, start: String) -> Self {
+impl ValveNetwork {
+    fn new(valves: &[Valve], start: &str) -> Self {
+        let by_key = valves
+            .iter()
+            .map(|v| (v.key.as_str(), v))
+            .collect::<HashMap<&str, &Valve>>();
+
+        let mut keys = valves
+            .iter()
+            .filter(|v| v.flow_rate > 0)
+            .map(|v| v.key.as_str())
+            .collect::<Vec<&str>>();
+        keys.sort_unstable();
+        assert!(
+            keys.len() <= ValveSet::CAPACITY,
+            "too many working valves to track"
+        );
+        let flow_rates = keys.iter().map(|k| by_key[k].flow_rate).collect();
+        if !keys.contains(&start) {
+            keys.push(start);
+        }
+
+        let distances = keys
+            .iter()
+            .map(|from| {
+                let steps = search::bfs_all([*from], |key| {
+                    by_key[key].neighbors.iter().map(|n| n.as_str())
+                });
+                keys.iter()
+                    .map(|to| steps.get(to).copied().unwrap_or(usize::MAX))
+                    .collect()
+            })
+            .collect();
+
         Self {
-            network,
-            visited: HashSet::new(),
-            to_visit: vec![(1, start)],
+            flow_rates,
+            distances,
+            start: keys.iter().position(|k| *k == start).unwrap(),
         }
     }
-}
 
-impl<'a> Iterator for ValveNetworkBFSIter<'a> {
-    type Item = (usize, &'a Valve);
+    /// The most pressure released by any single route that opens exactly
+    /// each set of valves within `time` minutes.
+    fn best_per_set(&self, time: usize) -> HashMap<ValveSet, usize> {
+        let mut best = HashMap::new();
+        self.visit(&mut best, self.start, time, ValveSet::new(), 0);
+        best
+    }
+
+    fn visit(
+        &self,
+        best: &mut HashMap<ValveSet, usize>,
+        at: usize,
+        time_left: usize,
+        open_valves: ValveSet,
+        released: usize,
+    ) {
+        let current = best.entry(open_valves).or_insert(0);
+        *current = released.max(*current);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.to_visit.is_empty() {
-            return None;
+        for (next, flow_rate) in self.flow_rates.iter().enumerate() {
+            // walking there and then a minute to open it
+            let cost = self.distances[at][next].saturating_add(1);
+            if cost >= time_left || open_valves.contains(next) {
+                continue;
+            }
+            let time_left = time_left - cost;
+            self.visit(
+                best,
+                next,
+                time_left,
+                open_valves.with(next),
+                released + flow_rate * time_left,
+            );
         }
+    }
 
-        let next = self.to_visit.drain(0..1).next()?;
+    /// The most pressure `agents` working together can release in `time`
+    /// minutes, each taking their own route and never opening the same valve.
+    fn max_release(&self, time: usize, agents: usize) -> usize {
+        let best = self.best_per_set(time);
+        if self.flow_rates.len() <= DENSE_LIMIT {
+            combine_dense(&best, self.flow_rates.len(), agents)
+        } else {
+            combine_sparse(&best, agents)
+        }
+    }
+}
 
-        let valve = self.network.0.get(&next.1).unwrap();
-        self.visited.insert(next.1);
+fn to_mask(set: &ValveSet) -> usize {
+    set.iter().fold(0, |mask, i| mask | 1 << i)
+}
 
-        #[allow(clippy::needless_collect)]
-        let to_visit = valve
-            .neighbors
-            .iter()
-            .map(|v| (next.0 + v.0, v.1.clone()))
-            .filter(|v| {
-                let visited = !self.visited.contains(&*v.1);
-                let to_visited = !self.to_visit.contains(v);
-                visited && to_visited
-            })
-            .collect::<Vec<(usize, String)>>();
+/// Splits every valve between the agents using tables over all subsets of
+/// the `valves` working valves.
+fn combine_dense(best: &HashMap<ValveSet, usize>, valves: usize, agents: usize) -> usize {
+    let size = 1 << valves;
+    let full = size - 1;
 
-        self.to_visit.extend(to_visit.into_iter());
+    // the best single route opening any subset of each set, which is a
+    // subset-max (superset) DP over the routes' exact sets
+    let mut within = vec![0; size];
+    for (set, released) in best.iter() {
+        let mask = to_mask(set);
+        within[mask] = within[mask].max(*released);
+    }
+    for bit in 0..valves {
+        for mask in 0..size {
+            if mask & 1 << bit != 0 {
+                within[mask] = within[mask].max(within[mask ^ 1 << bit]);
+            }
+        }
+    }
 
-        Some((next.0, valve))
+    // the best each team can do from each set, adding one agent at a time by
+    // trying every way of splitting the set between them and the newcomer
+    let mut team = within.clone();
+    for _ in 2..agents {
+        let mut bigger = vec![0; size];
+        for (mask, best) in bigger.iter_mut().enumerate() {
+            let mut sub = mask;
+            loop {
+                *best = (*best).max(within[sub] + team[mask ^ sub]);
+                if sub == 0 {
+                    break;
+                }
+                sub = (sub - 1) & mask;
+            }
+        }
+        team = bigger;
     }
-}
-#[derive(Default, Debug, Clone)]
-struct ValveNetwork(HashMap<String, Valve>);
 
-impl ValveNetwork {
-    fn bfs_iter(&self, start: String) -> ValveNetworkBFSIter {
-        ValveNetworkBFSIter::new(self, start)
+    // only the whole set matters for the last agent
+    match agents {
+        0 => 0,
+        1 => team[full],
+        _ => (0..size)
+            .map(|mask| within[mask] + team[full ^ mask])
+            .max()
+            .unwrap_or(0),
     }
+}
 
-    fn find_optimal_flow(
-        &self,
-        dp: &mut HashMap<u32, usize>,
-        current_key: &str,
-        time_left: usize,
-        open_valves: u32, // bit mask of open valves
-        flowed: usize,
-    ) {
-        let current = dp.get(&open_valves).unwrap_or(&0).to_owned();
-        dp.insert(open_valves, flowed.max(current));
+/// Picks a disjoint route per agent from the best routes, most pressure first,
+/// giving up on any branch that can't beat the best found so far.
+fn combine_sparse(best: &HashMap<ValveSet, usize>, agents: usize) -> usize {
+    let mut routes = best
+        .iter()
+        .map(|(set, released)| (*set, *released))
+        .collect::<Vec<_>>();
+    routes.sort_unstable_by_key(|(_, released)| std::cmp::Reverse(*released));
 
-        self.0
-            .get(current_key)
-            .unwrap()
-            .neighbors
-            .iter()
-            .map(|(mins, key)| (mins, self.0.get(key).unwrap()))
-            .filter(|(mins, v)| *mins < &time_left && (v.bit_mask & open_valves) == 0)
-            .for_each(|(mins, v)| {
-                let time_left = time_left - mins;
-                self.find_optimal_flow(
-                    dp,
-                    &v.key,
-                    time_left,
-                    open_valves | v.bit_mask,
-                    flowed + (v.flow_rate * time_left),
+    fn pick(
+        routes: &[(ValveSet, usize)],
+        agents: usize,
+        used: ValveSet,
+        released: usize,
+        best: &mut usize,
+    ) {
+        *best = released.max(*best);
+        if agents == 0 {
+            return;
+        }
+        for (i, (set, route)) in routes.iter().enumerate() {
+            // the routes only get worse from here
+            if released + route * agents <= *best {
+                break;
+            }
+            if set.is_disjoint(&used) {
+                pick(
+                    &routes[i + 1..],
+                    agents - 1,
+                    used | *set,
+                    released + route,
+                    best,
                 );
-            });
+            }
+        }
     }
+
+    let mut max_release = 0;
+    pick(&routes, agents, ValveSet::new(), 0, &mut max_release);
+    max_release
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct Solution {
     input: ValveNetwork,
+    part1_time: usize,
+    part2_time: usize,
+    part2_agents: usize,
+}
+
+impl Default for Solution {
+    fn default() -> Self {
+        Self {
+            input: ValveNetwork::default(),
+            part1_time: 30,
+            // the elephant takes as long to teach as it saves
+            part2_time: 26,
+            part2_agents: 2,
+        }
+    }
 }
 
 impl Solver for Solution {
@@ -128,67 +261,18 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        let network = ValveNetwork(
-            input
-                .lines()
-                .map(|l| {
-                    let valve = l.parse::<Valve>().unwrap();
-                    (valve.key.clone(), valve)
-                })
-                .collect::<HashMap<String, Valve>>(),
-        );
-
-        // compact network using breadth first search iterator
-        let compact_network = ValveNetwork(
-            network
-                .0
-                .iter()
-                .filter(|(_, v)| v.flow_rate > 0 || v.key == *"AA")
-                .enumerate()
-                .map(|(i, (key, v))| {
-                    (
-                        key.clone(),
-                        Valve {
-                            bit_mask: 2u32.pow(i as u32),
-                            key: key.clone(),
-                            flow_rate: v.flow_rate,
-                            // using network iterator we can build a complete
-                            // list of navigatable neighbors ordered by weight
-                            neighbors: network
-                                .bfs_iter(key.clone())
-                                .filter(|v| &v.1.key != key && v.1.flow_rate > 0)
-                                .map(|(mins, v)| (mins, v.key.clone()))
-                                .collect::<Vec<(usize, String)>>(),
-                        },
-                    )
-                })
-                .collect::<HashMap<String, Valve>>(),
-        );
-
-        self.input = compact_network;
+        let valves = parse::lines(input, |l| l.parse::<Valve>()).unwrap();
+        self.input = ValveNetwork::new(&valves, "AA");
     }
 
     fn solve_part1(&self) -> String {
-        let mut solutions = HashMap::new();
-        self.input.find_optimal_flow(&mut solutions, "AA", 30, 0, 0);
-
-        let max_flow = solutions.iter().max_by_key(|s| s.1).unwrap();
-
-        max_flow.1.to_string()
+        self.input.max_release(self.part1_time, 1).to_string()
     }
 
     fn solve_part2(&self) -> String {
-        let mut solutions = HashMap::new();
-        self.input.find_optimal_flow(&mut solutions, "AA", 26, 0, 0);
-        let mut max_flow = 0;
-        for (k1, v1) in solutions.iter() {
-            for (k2, v2) in solutions.iter() {
-                if (k1 & k2) == 0 {
-                    max_flow = max_flow.max(v1 + v2);
-                }
-            }
-        }
-        max_flow.to_string()
+        self.input
+            .max_release(self.part2_time, self.part2_agents)
+            .to_string()
     }
 }
 
@@ -226,6 +310,33 @@ Valve JJ has flow rate=21; tunnel leads to valve II"
         assert_eq!(solution, "1707");
     }
 
+    #[test]
+    fn test_custom_agents() {
+        // on your own with the full half hour is just part 1 again
+        let mut solver = Solution {
+            part2_time: 30,
+            part2_agents: 1,
+            ..Default::default()
+        };
+        solver.with_input(get_example_input());
+        assert_eq!(solver.solve_part2(), "1651");
+    }
+
+    #[test]
+    fn test_combine_dense_matches_sparse() {
+        let mut solver = Solution::default();
+        solver.with_input(solver.get_input());
+        let network = &solver.input;
+        for (time, agents) in [(30, 1), (26, 2), (20, 3), (16, 4)] {
+            let best = network.best_per_set(time);
+            assert_eq!(
+                combine_dense(&best, network.flow_rates.len(), agents),
+                combine_sparse(&best, agents),
+                "{agents} agents with {time} minutes"
+            );
+        }
+    }
+
     #[test]
     fn test_solution_part1() {
         let mut solver = Solution::default();
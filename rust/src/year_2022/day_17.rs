@@ -1,219 +1,228 @@
-use std::{collections::HashMap, hash::Hash, thread::sleep, time::Duration};
+use std::{str::FromStr, thread::sleep, time::Duration};
 
-use crate::{solver::Solver, utils::clear_terminal};
+use crate::{
+    solver::Solver,
+    utils::{clear_terminal, cycle::find_cycle, point::Direction},
+};
 
-const MIN_X: u8 = 0;
-const MAX_X: u8 = 7;
+/// The rocks from the puzzle, in the order they fall.
+const SHAPES: &str = "\
+####
 
-#[derive(Debug)]
-enum Dir {
-    Left,
-    Right,
-}
+.#.
+###
+.#.
 
-impl From<char> for Dir {
-    fn from(value: char) -> Self {
-        if value == '<' {
-            Self::Left
-        } else {
-            Self::Right
-        }
-    }
-}
+..#
+..#
+###
 
-#[derive(Debug)]
-enum Shape {
-    Flat,
-    Cross,
-    Angle,
-    Tall,
-    Block,
+#
+#
+#
+#
+
+##
+##";
+
+const CHAMBER_WIDTH: usize = 7;
+
+// rocks appear this far from the left wall and this far above the tower
+const SPAWN_X: usize = 2;
+const SPAWN_GAP: usize = 3;
+
+/// A rock as one bitmask per row, bottom row first, where bit `x` is the
+/// column `x` in from the left edge of the rock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Shape {
+    rows: Vec<u64>,
+    width: usize,
 }
 
-impl Shape {
-    fn get_pieces(&self, x: u8) -> [u8; 4] {
-        match self {
-            Shape::Flat => [0b1111000 >> x, 0, 0, 0],
-            Shape::Cross => [0b0100000 >> x, 0b1110000 >> x, 0b0100000 >> x, 0],
-            Shape::Angle => [0b1110000 >> x, 0b0010000 >> x, 0b0010000 >> x, 0],
-            Shape::Tall => [
-                0b1000000 >> x,
-                0b1000000 >> x,
-                0b1000000 >> x,
-                0b1000000 >> x,
-            ],
-            Shape::Block => [0b1100000 >> x, 0b1100000 >> x, 0, 0],
+impl FromStr for Shape {
+    type Err = String;
+    /// Blank rows and columns around the rock are trimmed off, so it sits
+    /// right against whatever it lands on.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rows = Vec::new();
+        for line in s.lines().rev() {
+            if line.len() > u64::BITS as usize {
+                return Err(format!("shape row too wide: {line}"));
+            }
+            let mut row = 0u64;
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => row |= 1 << x,
+                    '.' => {}
+                    _ => return Err(format!("bad shape character {c}")),
+                }
+            }
+            rows.push(row);
         }
+
+        let (Some(bottom), Some(top)) = (
+            rows.iter().position(|&r| r != 0),
+            rows.iter().rposition(|&r| r != 0),
+        ) else {
+            return Err("empty shape".to_string());
+        };
+        let left = rows.iter().map(|r| r.trailing_zeros()).min().unwrap_or(0);
+        let rows = rows[bottom..=top]
+            .iter()
+            .map(|r| r >> left)
+            .collect::<Vec<_>>();
+        let width = rows
+            .iter()
+            .map(|r| (u64::BITS - r.leading_zeros()) as usize)
+            .max()
+            .unwrap_or(0);
+        Ok(Self { rows, width })
     }
 }
 
-#[derive(Debug, Hash, PartialEq, Eq)]
-struct P(u8, usize);
+/// Reads shapes drawn with `#` and `.`, separated by blank lines.
+fn parse_shapes(s: &str) -> Result<Vec<Shape>, String> {
+    s.split("\n\n").map(|shape| shape.parse()).collect()
+}
 
 struct Rock<'a> {
     shape: &'a Shape,
-    pos: P,
+    x: usize,
+    y: usize,
 }
 
 impl Rock<'_> {
-    fn can_move_down(&self, rocks: &[u8]) -> bool {
-        // base case is rock is at y == 0 on the floor
-        if self.pos.1 == 0 {
-            return false;
-        }
-
-        !self
-            .shape
-            .get_pieces(self.pos.0)
-            .iter()
-            .enumerate()
-            .any(|(y, p)| {
-                if let Some(r) = rocks.get(y + self.pos.1 - 1) {
-                    r & p != 0
-                } else {
-                    false
-                }
-            })
+    /// Whether the rock would fit with its bottom left corner at `(x, y)`,
+    /// clear of the walls and every settled rock.
+    fn fits(&self, rocks: &[u64], width: usize, x: usize, y: usize) -> bool {
+        x + self.shape.width <= width
+            && self
+                .shape
+                .rows
+                .iter()
+                .enumerate()
+                .all(|(dy, row)| rocks.get(y + dy).is_none_or(|r| r & (row << x) == 0))
     }
 
-    fn can_move_left(&self, rocks: &[u8]) -> bool {
-        if self.pos.0 == MIN_X {
-            return false;
-        }
-
-        !self
-            .shape
-            .get_pieces(self.pos.0 - 1)
-            .iter()
-            .enumerate()
-            .any(|(y, p)| {
-                if let Some(r) = rocks.get(y + self.pos.1) {
-                    r & p != 0
-                } else {
-                    false
-                }
-            })
-    }
-
-    fn can_move_right(&self, rocks: &[u8]) -> bool {
-        let x = self.pos.0 + 1;
-
-        if match self.shape {
-            Shape::Flat => x + 3 == MAX_X,
-            Shape::Cross => x + 2 == MAX_X,
-            Shape::Angle => x + 2 == MAX_X,
-            Shape::Tall => x == MAX_X,
-            Shape::Block => x + 1 == MAX_X,
-        } {
-            return false;
-        }
-
-        !self.shape.get_pieces(x).iter().enumerate().any(|(y, p)| {
-            if let Some(r) = rocks.get(y + self.pos.1) {
-                r & p != 0
-            } else {
-                false
+    /// Moves the rock one step in `dir` if there's room, returning whether
+    /// it moved.
+    fn push(&mut self, rocks: &[u64], width: usize, dir: Direction) -> bool {
+        // y counts up from the floor, so down is towards 0
+        let target = match dir {
+            Direction::Left => self.x.checked_sub(1).map(|x| (x, self.y)),
+            Direction::Right => Some((self.x + 1, self.y)),
+            Direction::Down => self.y.checked_sub(1).map(|y| (self.x, y)),
+            Direction::Up => Some((self.x, self.y + 1)),
+        };
+        match target {
+            Some((x, y)) if self.fits(rocks, width, x, y) => {
+                (self.x, self.y) = (x, y);
+                true
             }
-        })
+            _ => false,
+        }
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct Solution {
-    jets: Vec<Dir>,
+    jets: Vec<Direction>,
     shapes: Vec<Shape>,
+    width: usize,
 }
 
-// how many rows of rocks to use in cache key
-const WINDOW_SIZE: usize = 3;
-type CacheKey = ([u8; WINDOW_SIZE], usize, usize);
-
-impl Solution {
-    fn get_rock_height(&self, target_rock_count: u64) -> u64 {
-        let mut jets = self.jets.iter().enumerate().cycle();
-        let mut shapes = self.shapes.iter().enumerate().cycle();
-        let mut rocks: Vec<u8> = Vec::new();
-        let mut pattern_cache: HashMap<CacheKey, (u64, u64)> = HashMap::new();
-
-        let mut extra_height = 0;
-        let mut rock_count = 0;
-        while rock_count < target_rock_count {
-            rock_count += 1;
-            let (shape_index, shape) = shapes.next().unwrap();
-            let mut rock = Rock {
-                pos: P(MIN_X + 2, rocks.len() + 3),
-                shape,
-            };
-
-            // I don't know why linter complains about this
-            // not being used when it clearly is...
-            #[allow(unused_assignments)]
-            let mut jet_index = 0;
-            loop {
-                let jet = jets.next().unwrap();
-                jet_index = jet.0;
-                match jet.1 {
-                    Dir::Left => {
-                        if rock.can_move_left(&rocks) {
-                            rock.pos.0 -= 1;
-                        }
-                    }
-                    Dir::Right => {
-                        if rock.can_move_right(&rocks) {
-                            rock.pos.0 += 1;
-                        }
-                    }
-                }
+impl Default for Solution {
+    fn default() -> Self {
+        Self {
+            jets: Vec::new(),
+            shapes: parse_shapes(SHAPES).unwrap(),
+            width: CHAMBER_WIDTH,
+        }
+    }
+}
 
-                if rock.can_move_down(&rocks) {
-                    rock.pos.1 -= 1;
-                } else {
-                    break;
-                }
-            }
+// how many rows from the top of the tower to use in the cycle key, enough
+// that a falling rock can't tell the difference below them
+const WINDOW_SIZE: usize = 32;
+type CacheKey = ([u64; WINDOW_SIZE], usize, usize);
+
+/// The settled rocks and where we're up to in the jet and shape sequences.
+struct Chamber<'a> {
+    jets: &'a [Direction],
+    shapes: &'a [Shape],
+    width: usize,
+    rocks: Vec<u64>,
+    jet_index: usize,
+    shape_index: usize,
+}
 
-            let pieces = rock.shape.get_pieces(rock.pos.0);
+impl Chamber<'_> {
+    fn drop_rock(&mut self) {
+        let shape = &self.shapes[self.shape_index];
+        self.shape_index = (self.shape_index + 1) % self.shapes.len();
+        let mut rock = Rock {
+            x: SPAWN_X.min(self.width - shape.width),
+            y: self.rocks.len() + SPAWN_GAP,
+            shape,
+        };
 
-            for (y, section) in pieces.iter().enumerate() {
-                // some sections of our shapes are just padding
-                // so we can have arrays
-                if section == &0u8 {
-                    break;
-                }
+        loop {
+            let jet = self.jets[self.jet_index];
+            self.jet_index = (self.jet_index + 1) % self.jets.len();
+            rock.push(&self.rocks, self.width, jet);
 
-                let y = y + rock.pos.1;
-                if y >= rocks.len() {
-                    rocks.push(*section);
-                } else {
-                    let new_section = rocks[y] | section;
-                    rocks[y] = new_section;
-                }
+            if !rock.push(&self.rocks, self.width, Direction::Down) {
+                break;
             }
+        }
 
-            if rocks.len() >= WINDOW_SIZE {
-                let mut last_group: [u8; WINDOW_SIZE] = [0; WINDOW_SIZE];
-                last_group.clone_from_slice(&rocks[rocks.len() - WINDOW_SIZE..]);
-
-                let key = (last_group, jet_index, shape_index);
-                if let Some((old_height, old_rock_count)) = pattern_cache.get(&key) {
-                    let rock_diff = rock_count - *old_rock_count;
-                    if rock_diff < 50 {
-                        continue;
-                    }
-
-                    let height_diff = rocks.len() as u64 - *old_height;
-                    let catchup = (target_rock_count - rock_diff) / rock_diff;
-                    extra_height += height_diff * catchup;
-                    rock_count += rock_diff * catchup;
-                }
-
-                pattern_cache.insert(key, (rocks.len() as u64, rock_count));
+        for (dy, row) in rock.shape.rows.iter().enumerate() {
+            let y = rock.y + dy;
+            if y >= self.rocks.len() {
+                self.rocks.resize(y + 1, 0);
             }
+            self.rocks[y] |= row << rock.x;
+        }
 
-            // print_view(&rock, &rocks);
+        // print_view(&rock, &self.rocks, self.width);
+    }
+
+    fn key(&self) -> CacheKey {
+        let mut top = [0; WINDOW_SIZE];
+        for (row, rocks) in top.iter_mut().zip(self.rocks.iter().rev()) {
+            *row = *rocks;
         }
+        (top, self.jet_index, self.shape_index)
+    }
+}
 
-        rocks.len() as u64 + extra_height
+impl Solution {
+    fn get_rock_height(&self, target_rock_count: u64) -> u64 {
+        assert!(
+            self.width <= u64::BITS as usize,
+            "chambers are at most 64 wide"
+        );
+        assert!(
+            self.shapes.iter().all(|s| s.width <= self.width),
+            "a shape is wider than the chamber"
+        );
+
+        let chamber = Chamber {
+            jets: &self.jets,
+            shapes: &self.shapes,
+            width: self.width,
+            rocks: Vec::new(),
+            jet_index: 0,
+            shape_index: 0,
+        };
+
+        find_cycle(
+            chamber,
+            Chamber::key,
+            |c| c.rocks.len() as u64,
+            Chamber::drop_rock,
+        )
+        .extrapolate(target_rock_count as usize)
     }
 }
 
@@ -223,14 +232,11 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.jets = input.chars().map(Dir::from).collect();
-        self.shapes = vec![
-            Shape::Flat,
-            Shape::Cross,
-            Shape::Angle,
-            Shape::Tall,
-            Shape::Block,
-        ];
+        self.jets = input
+            .trim()
+            .chars()
+            .map(|c| Direction::try_from(c).unwrap())
+            .collect();
     }
 
     fn solve_part1(&self) -> String {
@@ -267,6 +273,54 @@ mod test {
         assert_eq!(solution, "1514285714288");
     }
 
+    #[test]
+    fn test_shape_padding() {
+        let padded = ".....\n..#..\n.###.\n..#..\n.....".parse::<Shape>();
+        let plus = ".#.\n###\n.#.".parse::<Shape>();
+        assert_eq!(padded, plus);
+        assert_eq!(plus.map(|s| s.width), Ok(3));
+
+        // an empty column or row would otherwise keep it off the wall or floor
+        let shape = ".#\n.#\n..".parse::<Shape>().unwrap();
+        assert_eq!(shape.rows, vec![1, 1]);
+        assert_eq!(shape.width, 1);
+        assert!("..\n..".parse::<Shape>().is_err());
+    }
+
+    #[test]
+    fn test_custom_chambers() {
+        // rocks that fill a whole row can only ever stack up
+        let mut solver = Solution {
+            shapes: parse_shapes("###").unwrap(),
+            width: 3,
+            ..Default::default()
+        };
+        solver.with_input("<>");
+        assert_eq!(solver.get_rock_height(2022), 2022);
+
+        let mut solver = Solution {
+            shapes: parse_shapes(&"#".repeat(64)).unwrap(),
+            width: 64,
+            ..Default::default()
+        };
+        solver.with_input(">");
+        assert_eq!(solver.get_rock_height(100), 100);
+
+        // pushed straight down, the usual rocks pile up where they appear
+        let mut solver = Solution::default();
+        solver.with_input("v");
+        assert_eq!(solver.get_rock_height(5), 11);
+
+        assert!(parse_shapes("#x#").is_err());
+        assert_eq!(
+            parse_shapes(".#\n##").unwrap(),
+            vec![Shape {
+                rows: vec![0b11, 0b10],
+                width: 2
+            }]
+        );
+    }
+
     #[test]
     fn test_solution_part1() {
         let mut solver = Solution::default();
@@ -285,31 +339,29 @@ mod test {
 }
 
 #[allow(dead_code)]
-fn print_view(rock: &Rock, rocks: &Vec<u8>) {
+fn print_view(rock: &Rock, rocks: &[u64], width: usize) {
     clear_terminal();
     let max_y = rocks.len();
     println!("--{max_y:0>4}--");
     let max_y = max_y.max(30);
     let min_y = if max_y > 30 { max_y - 30 } else { 0 };
-    let shape = rock.shape.get_pieces(rock.pos.0);
     for y in (min_y..max_y + 10).rev() {
-        print!("|");
-        let rocks = rocks.get(y).unwrap_or(&0u8);
-        let rock = if y >= rock.pos.1 && y < rock.pos.1 + shape.len() {
-            shape.get(y - rock.pos.1).unwrap_or(&0u8)
-        } else {
-            &0u8
-        };
-
-        let line = format!("{:#09b}", rock | rocks)
-            .trim_start_matches("0b")
-            .replace('0', ".")
-            .replace('1', "X");
-
-        print!("{line}");
-        println!("|{:0>4}", y + 1);
+        let settled = rocks.get(y).copied().unwrap_or(0);
+        let falling = y
+            .checked_sub(rock.y)
+            .and_then(|dy| rock.shape.rows.get(dy))
+            .map_or(0, |row| row << rock.x);
+
+        let line = (0..width)
+            .map(|x| match (settled >> x & 1, falling >> x & 1) {
+                (_, 1) => '@',
+                (1, _) => '#',
+                _ => '.',
+            })
+            .collect::<String>();
+        println!("|{line}|{:0>4}", y + 1);
         if y == 0 {
-            println!("+-------+");
+            println!("+{}+", "-".repeat(width));
         }
     }
     sleep(Duration::from_millis(140));
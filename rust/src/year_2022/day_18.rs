@@ -1,42 +1,30 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    i8,
-    ops::Add,
+use crate::{
+    solver::Solver,
+    utils::{bitset::BitGrid, point::Point3, search},
 };
 
-use crate::solver::Solver;
+type Pos = Point3<i8>;
 
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-struct Pos(i8, i8, i8);
-
-impl From<&str> for Pos {
-    fn from(value: &str) -> Self {
-        let d = value
-            .split(',')
-            .map(|n| n.parse().unwrap())
-            .collect::<Vec<i8>>();
-        Self(d[0], d[1], d[2])
-    }
-}
-
-impl Add for Pos {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
-    }
+/// The grid cell for a position, if it has one.
+fn cell(p: &Pos) -> Option<[usize; 3]> {
+    Some([
+        usize::try_from(p.x).ok()?,
+        usize::try_from(p.y).ok()?,
+        usize::try_from(p.z).ok()?,
+    ])
 }
 
-// impl std::ops::AddAssign for Pos {
-//     fn add_assign(&mut self, rhs: Self) {
-//         self.0 += rhs.0;
-//         self.1 += rhs.1;
-//         self.2 += rhs.2;
-//     }
-// }
-
 #[derive(Default, Debug)]
 pub struct Solution {
-    input: HashSet<Pos>,
+    cubes: Vec<Pos>,
+    // shifted in by one so there's a layer of air on every side
+    droplet: BitGrid<3>,
+}
+
+impl Solution {
+    fn is_lava(&self, p: &Pos) -> bool {
+        cell(p).is_some_and(|c| self.droplet.contains(c))
+    }
 }
 
 impl Solver for Solution {
@@ -45,87 +33,47 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.input = input.lines().map(|l| l.into()).collect();
+        self.cubes = input
+            .lines()
+            .map(|l| l.parse::<Pos>().unwrap() + Pos::new(1, 1, 1))
+            .collect();
+
+        let size = self
+            .cubes
+            .iter()
+            .filter_map(cell)
+            .fold([0; 3], |size, [x, y, z]| {
+                [size[0].max(x + 2), size[1].max(y + 2), size[2].max(z + 2)]
+            });
+        self.droplet = BitGrid::new(size);
+        for c in self.cubes.iter().filter_map(cell) {
+            self.droplet.insert(c);
+        }
     }
 
     fn solve_part1(&self) -> String {
-        let neighbors = [
-            Pos(1, 0, 0),
-            Pos(0, 1, 0),
-            Pos(0, 0, 1),
-            Pos(-1, 0, 0),
-            Pos(0, -1, 0),
-            Pos(0, 0, -1),
-        ];
-
-        self.input
+        self.cubes
             .iter()
-            .map(|p| {
-                6 - neighbors
-                    .iter()
-                    .filter(|v| self.input.contains(&(*p + **v)))
-                    .count()
-            })
+            .map(|p| 6 - p.neighbours_6().iter().filter(|n| self.is_lava(n)).count())
             .sum::<usize>()
             .to_string()
     }
 
     fn solve_part2(&self) -> String {
-        let mut min_x = i8::MAX;
-        let mut max_x = i8::MIN;
-        let mut min_y = i8::MAX;
-        let mut max_y = i8::MIN;
-        let mut min_z = i8::MAX;
-        let mut max_z = i8::MIN;
-
-        self.input.iter().for_each(|p| {
-            min_x = min_x.min(p.0);
-            max_x = max_x.max(p.0);
-            min_y = min_y.min(p.1);
-            max_y = max_y.max(p.1);
-            min_z = min_z.min(p.2);
-            max_z = max_z.max(p.2);
+        // flood fill the air around the droplet from a corner of its
+        // bounding box
+        let outside = search::bfs_all([Pos::new(0, 0, 0)], |p| {
+            p.neighbours_6().into_iter().filter(|n| {
+                cell(n).is_some_and(|c| self.droplet.in_bounds(c) && !self.droplet.contains(c))
+            })
         });
 
-        let mut outside: HashSet<Pos> = HashSet::new();
-
-        let neighbors = [
-            Pos(1, 0, 0),
-            Pos(0, 1, 0),
-            Pos(0, 0, 1),
-            Pos(-1, 0, 0),
-            Pos(0, -1, 0),
-            Pos(0, 0, -1),
-        ];
-
-        let mut outer_queue: VecDeque<Pos> = VecDeque::new();
-        outer_queue.push_back(Pos(min_x - 1, min_y - 1, min_z - 1));
-
-        while let Some(q) = outer_queue.pop_front() {
-            outside.insert(q);
-            for n in neighbors.iter() {
-                let next_outer = q + *n;
-                if min_x - 1 <= next_outer.0
-                    && next_outer.0 <= max_x + 1
-                    && min_y - 1 <= next_outer.1
-                    && next_outer.1 <= max_y + 1
-                    && min_z - 1 <= next_outer.2
-                    && next_outer.2 <= max_z + 1
-                    && !self.input.contains(&next_outer)
-                    && !outside.contains(&next_outer)
-                    && !outer_queue.contains(&next_outer)
-                {
-                    outer_queue.push_back(next_outer);
-                }
-            }
-        }
-
-        self.input
+        self.cubes
             .iter()
             .map(|p| {
-                neighbors
+                p.neighbours_6()
                     .iter()
-                    .filter(|v| outside.contains(&(*p + **v)))
+                    .filter(|n| outside.contains_key(n))
                     .count()
             })
             .sum::<usize>()
@@ -1,75 +1,169 @@
-use crate::solver::Solver;
-
-#[derive(Debug)]
-enum Mineral {
-    Ore,
-    Clay,
-    Obsidian,
-    Geode,
-    Unknown,
+use std::str::FromStr;
+
+use crate::{
+    solver::Solver,
+    utils::{
+        parallel::par_map,
+        parse::{self, parse, ParseError},
+    },
+};
+
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+    time_left: u32,
+    resources: [u32; 4],
+    robots: [u32; 4],
+}
+
+#[derive(Debug, Clone)]
+struct Blueprint {
+    idx: u8,
+    /// `costs[robot][mineral]` is how much of `mineral` building `robot` takes
+    costs: [[u32; 4]; 4],
+    /// we can only spend one robot's worth of each mineral per minute, so there
+    /// is never any point collecting faster than the most expensive recipe
+    max_spend: [u32; 4],
 }
 
-impl From<&str> for Mineral {
-    fn from(value: &str) -> Self {
-        match value {
-            "ore" => Mineral::Ore,
-            "clay" => Mineral::Clay,
-            "obsidian" => Mineral::Obsidian,
-            "geode" => Mineral::Geode,
-            _ => Mineral::Unknown,
+impl FromStr for Blueprint {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (idx, ore_ore, clay_ore, obsidian_ore, obsidian_clay, geode_ore, geode_obsidian) = parse!(
+            "Blueprint {}: \
+            Each ore robot costs {} ore. \
+            Each clay robot costs {} ore. \
+            Each obsidian robot costs {} ore and {} clay. \
+            Each geode robot costs {} ore and {} obsidian.",
+            s
+        )?;
+
+        let mut costs = [[0; 4]; 4];
+        costs[ORE][ORE] = ore_ore;
+        costs[CLAY][ORE] = clay_ore;
+        costs[OBSIDIAN][ORE] = obsidian_ore;
+        costs[OBSIDIAN][CLAY] = obsidian_clay;
+        costs[GEODE][ORE] = geode_ore;
+        costs[GEODE][OBSIDIAN] = geode_obsidian;
+
+        let mut max_spend = [u32::MAX; 4];
+        for mineral in [ORE, CLAY, OBSIDIAN] {
+            max_spend[mineral] = costs.iter().map(|c| c[mineral]).max().unwrap();
         }
+
+        Ok(Self {
+            idx,
+            costs,
+            max_spend,
+        })
     }
 }
 
-#[derive(Debug)]
-struct Robot {
-    ore: Mineral,
-    cost: Vec<(Mineral, u8)>,
-}
+impl Blueprint {
+    fn max_geodes(&self, time: u32) -> u32 {
+        let start = State {
+            time_left: time,
+            resources: [0; 4],
+            robots: [1, 0, 0, 0],
+        };
+        let mut best = 0;
+        self.search(start, &mut best);
+        best
+    }
+
+    fn search(&self, state: State, best: &mut u32) {
+        let banked = state.resources[GEODE] + state.robots[GEODE] * state.time_left;
+        *best = (*best).max(banked);
+
+        if self.upper_bound(&state) <= *best {
+            return;
+        }
+
+        // try geode robots first so good solutions tighten the bound early
+        for robot in (0..4).rev() {
+            if state.robots[robot] >= self.max_spend[robot] {
+                continue;
+            }
 
-impl From<&str> for Robot {
-    fn from(value: &str) -> Self {
-        let (ore, cost) = value
-            .trim_start_matches(" Each ")
-            .split_once(" robot costs ")
-            .unwrap();
-        Self {
-            ore: ore.into(),
-            cost: cost
-                .split(" and ")
-                .map(|c| {
-                    let (amt, ore) = c.split_once(' ').unwrap();
-                    (ore.into(), amt.parse().unwrap())
-                })
-                .collect(),
+            if let Some(next) = self.build_next(&state, robot) {
+                self.search(next, best);
+            }
         }
     }
-}
 
-#[derive(Debug)]
-struct Blueprint {
-    idx: u8,
-    robots: Vec<Robot>,
-}
+    /// Skips ahead to the minute `robot` can be afforded and built, rather than
+    /// branching on every minute spent waiting.
+    fn build_next(&self, state: &State, robot: usize) -> Option<State> {
+        let mut wait = 0;
+        for (mineral, &cost) in self.costs[robot].iter().enumerate() {
+            if cost <= state.resources[mineral] {
+                continue;
+            }
+            if state.robots[mineral] == 0 {
+                return None;
+            }
+            let missing = cost - state.resources[mineral];
+            wait = wait.max(missing.div_ceil(state.robots[mineral]));
+        }
+
+        // a robot finished in the last minute never gets to collect anything
+        let elapsed = wait + 1;
+        if elapsed >= state.time_left {
+            return None;
+        }
+
+        let mut next = *state;
+        next.time_left -= elapsed;
+        for mineral in 0..4 {
+            next.resources[mineral] += state.robots[mineral] * elapsed;
+            next.resources[mineral] -= self.costs[robot][mineral];
+        }
+        next.robots[robot] += 1;
+        Some(next)
+    }
+
+    /// An optimistic geode count assuming ore is free and that a clay robot,
+    /// an obsidian robot and a geode robot can all be built every minute
+    /// whenever their clay/obsidian costs are covered.
+    fn upper_bound(&self, state: &State) -> u32 {
+        let [_, mut clay, mut obsidian, mut geodes] = state.resources;
+        let [_, mut clay_robots, mut obsidian_robots, mut geode_robots] = state.robots;
+
+        for _ in 0..state.time_left {
+            let build_obsidian = clay >= self.costs[OBSIDIAN][CLAY];
+            let build_geode = obsidian >= self.costs[GEODE][OBSIDIAN];
 
-impl From<&str> for Blueprint {
-    fn from(value: &str) -> Self {
-        let (idx, robots) = value
-            .trim_start_matches("Blueprint ")
-            .split_once(':')
-            .unwrap();
-
-        Self {
-            idx: idx.parse().unwrap(),
-            robots: robots
-                .trim_end_matches('.')
-                .split('.')
-                .map(|r| r.into())
-                .collect(),
+            clay += clay_robots;
+            obsidian += obsidian_robots;
+            geodes += geode_robots;
+
+            if build_obsidian {
+                clay -= self.costs[OBSIDIAN][CLAY];
+                obsidian_robots += 1;
+            }
+            if build_geode {
+                obsidian -= self.costs[GEODE][OBSIDIAN];
+                geode_robots += 1;
+            }
+            clay_robots += 1;
         }
+
+        geodes
     }
 }
 
+/// Evaluates each blueprint on its own worker, returning `(idx, max_geodes)`
+/// pairs in blueprint order.
+fn evaluate_all(blueprints: &[Blueprint], time: u32) -> Vec<(u8, u32)> {
+    par_map(blueprints, |blueprint| {
+        (blueprint.idx, blueprint.max_geodes(time))
+    })
+}
+
 #[derive(Default, Debug)]
 pub struct Solution {
     input: Vec<Blueprint>,
@@ -81,16 +175,24 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.input = input.lines().map(|l| l.into()).collect()
+        self.input = parse::lines(input, |l| l.parse()).unwrap();
     }
 
     fn solve_part1(&self) -> String {
-        println!("{:#?}", self.input);
-        "".to_string()
+        evaluate_all(&self.input, 24)
+            .iter()
+            .map(|(idx, geodes)| *idx as u32 * geodes)
+            .sum::<u32>()
+            .to_string()
     }
 
     fn solve_part2(&self) -> String {
-        "".to_string()
+        let blueprints = &self.input[..self.input.len().min(3)];
+        evaluate_all(blueprints, 32)
+            .iter()
+            .map(|(_, geodes)| geodes)
+            .product::<u32>()
+            .to_string()
     }
 }
 
@@ -109,7 +211,7 @@ Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsid
         let mut solver = Solution::default();
         solver.with_input(get_example_input());
         let solution = solver.solve_part1();
-        assert_eq!(solution, "");
+        assert_eq!(solution, "33");
     }
 
     #[test]
@@ -117,7 +219,7 @@ Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsid
         let mut solver = Solution::default();
         solver.with_input(get_example_input());
         let solution = solver.solve_part2();
-        assert_eq!(solution, "");
+        assert_eq!(solution, "3472");
     }
 
     #[test]
@@ -125,7 +227,7 @@ Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsid
         let mut solver = Solution::default();
         solver.with_input(solver.get_input());
         let solution = solver.solve_part1();
-        assert_eq!(solution, "");
+        assert_eq!(solution, "1306");
     }
 
     #[test]
@@ -133,7 +235,7 @@ Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsid
         let mut solver = Solution::default();
         solver.with_input(solver.get_input());
         let solution = solver.solve_part2();
-        assert_eq!(solution, "");
+        assert_eq!(solution, "37604");
     }
 }
 
@@ -1,50 +1,198 @@
 use crate::solver::Solver;
 
+/// Where the grove coordinates are, counting on from the 0.
+const GROVE_OFFSETS: [usize; 3] = [1000, 2000, 3000];
+
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone)]
+struct Node {
+    value: i64,
+    priority: u64,
+    size: usize,
+    left: usize,
+    right: usize,
+    parent: usize,
+}
+
+/// The file as an implicit treap: a randomly balanced tree ordered by
+/// position in the list rather than by value, where every node knows the size
+/// of its subtree. Node `i` is always the `i`th number of the original file,
+/// so finding it is free and working out where it currently sits is a walk up
+/// to the root, which keeps moving a number O(log n).
+#[derive(Debug)]
+struct MixList {
+    nodes: Vec<Node>,
+    root: usize,
+}
+
+impl MixList {
+    fn new(values: impl IntoIterator<Item = i64>) -> Self {
+        // xorshift, the balance only needs the priorities to look random
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut list = Self {
+            nodes: Vec::new(),
+            root: NIL,
+        };
+        for value in values {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            list.nodes.push(Node {
+                value,
+                priority: seed,
+                size: 1,
+                left: NIL,
+                right: NIL,
+                parent: NIL,
+            });
+            list.root = list.merge(list.root, list.nodes.len() - 1);
+        }
+        list
+    }
+
+    fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    fn size(&self, node: usize) -> usize {
+        if node == NIL {
+            0
+        } else {
+            self.nodes[node].size
+        }
+    }
+
+    /// Fixes up a node's size and its children's parents after they change.
+    fn update(&mut self, node: usize) {
+        let Node { left, right, .. } = self.nodes[node];
+        self.nodes[node].size = 1 + self.size(left) + self.size(right);
+        for child in [left, right] {
+            if child != NIL {
+                self.nodes[child].parent = node;
+            }
+        }
+    }
+
+    /// Joins two trees, with everything in `a` ending up before `b`.
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL || b == NIL {
+            return if a == NIL { b } else { a };
+        }
+
+        if self.nodes[a].priority > self.nodes[b].priority {
+            let right = self.merge(self.nodes[a].right, b);
+            self.nodes[a].right = right;
+            self.update(a);
+            a
+        } else {
+            let left = self.merge(a, self.nodes[b].left);
+            self.nodes[b].left = left;
+            self.update(b);
+            b
+        }
+    }
+
+    /// Splits a tree into its first `k` numbers and the rest.
+    fn split(&mut self, node: usize, k: usize) -> (usize, usize) {
+        if node == NIL {
+            return (NIL, NIL);
+        }
+
+        let left_size = self.size(self.nodes[node].left);
+        if k <= left_size {
+            let (a, b) = self.split(self.nodes[node].left, k);
+            self.nodes[node].left = b;
+            self.update(node);
+            (a, node)
+        } else {
+            let (a, b) = self.split(self.nodes[node].right, k - left_size - 1);
+            self.nodes[node].right = a;
+            self.update(node);
+            (node, b)
+        }
+    }
+
+    fn set_root(&mut self, root: usize) {
+        self.root = root;
+        if root != NIL {
+            self.nodes[root].parent = NIL;
+        }
+    }
+
+    /// Where the `i`th number of the original file currently is.
+    fn position(&self, i: usize) -> usize {
+        let mut pos = self.size(self.nodes[i].left);
+        let mut node = i;
+        while node != self.root {
+            let parent = self.nodes[node].parent;
+            if self.nodes[parent].right == node {
+                pos += self.size(self.nodes[parent].left) + 1;
+            }
+            node = parent;
+        }
+        pos
+    }
+
+    /// Moves the `i`th number of the original file along by its value,
+    /// wrapping around as if the list were circular.
+    fn mix_one(&mut self, i: usize) {
+        let pos = self.position(i);
+        let (before, rest) = self.split(self.root, pos);
+        let (node, after) = self.split(rest, 1);
+        let others = self.merge(before, after);
+
+        // with the number taken out there are len - 1 gaps to land in
+        let new_pos = (pos as i64 + self.nodes[i].value).rem_euclid(self.size(others) as i64);
+        let (before, after) = self.split(others, new_pos as usize);
+        let joined = self.merge(before, node);
+        let root = self.merge(joined, after);
+        self.set_root(root);
+    }
+
+    fn iter(&self) -> impl Iterator<Item = i64> + '_ {
+        let mut stack = Vec::new();
+        let mut node = self.root;
+        std::iter::from_fn(move || {
+            while node != NIL {
+                stack.push(node);
+                node = self.nodes[node].left;
+            }
+            let next = stack.pop()?;
+            node = self.nodes[next].right;
+            Some(self.nodes[next].value)
+        })
+    }
+}
+
 #[derive(Default, Debug)]
 struct EncryptedFile {
     content: Vec<i64>,
 }
 
 impl EncryptedFile {
-    fn decrypt(&self, mix: u8, decryption_key: i64) -> i64 {
-        let mut output = self
-            .content
-            .iter()
-            .enumerate()
-            .map(|(i, n)| (*n * decryption_key, i as i64))
-            .collect::<Vec<(i64, i64)>>();
+    /// The file after applying the key and mixing it `mixes` times, rotated
+    /// round so it starts at the 0.
+    fn mix(&self, mixes: usize, decryption_key: i64) -> Vec<i64> {
+        let mut list = MixList::new(self.content.iter().map(|n| n * decryption_key));
 
-        let len = output.len() as i64;
-
-        for _ in 0..mix {
-            let mut cur = 0;
-            while cur < len {
-                for idx in 0..len as usize {
-                    let (n, i) = output[idx];
-                    if cur != i {
-                        continue;
-                    } else {
-                        let mut new_idx = (idx as i64 + n) % (len - 1);
-                        if new_idx <= 0 && n < 0 {
-                            new_idx += len - 1;
-                        }
-
-                        output.remove(idx);
-                        output.insert(new_idx as usize, (n, i));
-                        cur += 1;
-                    }
+        if list.len() > 1 {
+            for _ in 0..mixes {
+                for i in 0..list.len() {
+                    list.mix_one(i);
                 }
             }
         }
 
-        let zero = output.iter().position(|c| c.0 == 0).unwrap();
-
-        let len = output.len();
-        let a = output.get((zero + 1000) % len).unwrap();
-        let b = output.get((zero + 2000) % len).unwrap();
-        let c = output.get((zero + 3000) % len).unwrap();
+        let mut mixed = list.iter().collect::<Vec<i64>>();
+        let zero = mixed.iter().position(|n| *n == 0).unwrap();
+        mixed.rotate_left(zero);
+        mixed
+    }
 
-        a.0 + b.0 + c.0
+    fn decrypt(&self, mixes: usize, decryption_key: i64, offsets: &[usize]) -> i64 {
+        let mixed = self.mix(mixes, decryption_key);
+        offsets.iter().map(|o| mixed[o % mixed.len()]).sum()
     }
 }
 
@@ -65,11 +213,13 @@ impl Solver for Solution {
     }
 
     fn solve_part1(&self) -> String {
-        self.input.decrypt(1, 1).to_string()
+        self.input.decrypt(1, 1, &GROVE_OFFSETS).to_string()
     }
 
     fn solve_part2(&self) -> String {
-        self.input.decrypt(10, 811589153).to_string()
+        self.input
+            .decrypt(10, 811589153, &GROVE_OFFSETS)
+            .to_string()
     }
 }
 
@@ -104,6 +254,73 @@ mod test {
         assert_eq!(solution, "1623178306");
     }
 
+    /// The original mixer, which finds each number with a linear scan and
+    /// moves it with `Vec::remove` and `Vec::insert`.
+    fn mix_naive(content: &[i64], mixes: usize, decryption_key: i64) -> Vec<i64> {
+        let mut output = content
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (*n * decryption_key, i as i64))
+            .collect::<Vec<(i64, i64)>>();
+
+        let len = output.len() as i64;
+
+        for _ in 0..mixes {
+            let mut cur = 0;
+            while cur < len {
+                for idx in 0..len as usize {
+                    let (n, i) = output[idx];
+                    if cur != i {
+                        continue;
+                    } else {
+                        let mut new_idx = (idx as i64 + n) % (len - 1);
+                        if new_idx <= 0 && n < 0 {
+                            new_idx += len - 1;
+                        }
+
+                        output.remove(idx);
+                        output.insert(new_idx as usize, (n, i));
+                        cur += 1;
+                    }
+                }
+            }
+        }
+
+        let zero = output.iter().position(|c| c.0 == 0).unwrap();
+        output.rotate_left(zero);
+        output.into_iter().map(|(n, _)| n).collect()
+    }
+
+    #[test]
+    fn test_mix_matches_naive() {
+        let mut seed = 12345u64;
+        for len in [2, 3, 7, 50, 301] {
+            let mut content = (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    (seed >> 33) as i64 % 2000 - 1000
+                })
+                .collect::<Vec<i64>>();
+            content[len / 2] = 0;
+            content
+                .iter_mut()
+                .filter(|n| **n == 0)
+                .skip(1)
+                .for_each(|n| *n = 1);
+
+            let file = EncryptedFile {
+                content: content.clone(),
+            };
+            for (mixes, key) in [(1, 1), (3, 811589153), (10, -7)] {
+                assert_eq!(
+                    file.mix(mixes, key),
+                    mix_naive(&content, mixes, key),
+                    "{len} numbers mixed {mixes} times with key {key}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_solution_part1() {
         let mut solver = Solution::default();
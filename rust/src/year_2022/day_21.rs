@@ -1,8 +1,8 @@
-use std::{collections::HashMap, ops::Div, str::FromStr};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
 
-use crate::solver::Solver;
+use crate::{solver::Solver, utils::math::Rational};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Operation {
     Add,
     Sub,
@@ -23,6 +23,18 @@ impl FromStr for Operation {
     }
 }
 
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operation::Add => '+',
+            Operation::Sub => '-',
+            Operation::Mul => '*',
+            Operation::Div => '/',
+        };
+        write!(f, "{symbol}")
+    }
+}
+
 #[derive(Debug, Clone)]
 enum MonkeyOp {
     Value(i64),
@@ -59,48 +71,196 @@ impl FromStr for Monkey {
     }
 }
 
-#[derive(Default, Debug)]
-pub struct Solution {
-    input: HashMap<String, Monkey>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SolveError {
+    MissingMonkey(String),
+    NotAnEquation,
+    NonLinear,
+    DivisionByZero,
+    NoSolution,
+    Ambiguous,
 }
 
-fn resolve_monkey(monkeys: &HashMap<String, Monkey>, monkey_id: &str) -> i64 {
-    let monkey_op = monkeys.get(monkey_id).unwrap().op.clone();
-    match &monkey_op {
-        MonkeyOp::Value(value) => *value,
-        MonkeyOp::Operation { left, op, right } => {
-            let left_val = resolve_monkey(monkeys, left);
-            let right_val = resolve_monkey(monkeys, right);
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::MissingMonkey(id) => write!(f, "no monkey called {id}"),
+            SolveError::NotAnEquation => write!(f, "root doesn't compare two monkeys"),
+            SolveError::NonLinear => write!(f, "the equation isn't linear"),
+            SolveError::DivisionByZero => write!(f, "the equation divides by zero"),
+            SolveError::NoSolution => write!(f, "the equation has no solution"),
+            SolveError::Ambiguous => write!(f, "any value solves the equation"),
+        }
+    }
+}
+
+/// A monkey's job compiled down to a tree, with every branch that doesn't
+/// depend on the unknown already worked out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Const(Rational),
+    Unknown(String),
+    Op(Box<Expr>, Operation, Box<Expr>),
+}
+
+/// `coef * x + constant`, which is what any linear expression boils down to.
+#[derive(Debug, Clone, Copy)]
+struct Linear {
+    coef: Rational,
+    constant: Rational,
+}
+
+impl Expr {
+    fn compile(
+        monkeys: &HashMap<String, Monkey>,
+        monkey_id: &str,
+        unknown: Option<&str>,
+    ) -> Result<Expr, SolveError> {
+        if unknown == Some(monkey_id) {
+            return Ok(Expr::Unknown(monkey_id.to_string()));
+        }
 
-            match op {
-                Operation::Add => left_val.saturating_add(right_val),
-                Operation::Sub => left_val.saturating_sub(right_val),
-                Operation::Mul => left_val.saturating_mul(right_val),
-                Operation::Div => (left_val as f64).div(right_val as f64).round() as i64,
+        let monkey = monkeys
+            .get(monkey_id)
+            .ok_or_else(|| SolveError::MissingMonkey(monkey_id.to_string()))?;
+        match &monkey.op {
+            MonkeyOp::Value(value) => Ok(Expr::Const(Rational::from(*value))),
+            MonkeyOp::Operation { left, op, right } => {
+                let left = Expr::compile(monkeys, left, unknown)?;
+                let right = Expr::compile(monkeys, right, unknown)?;
+                match (left, right) {
+                    (Expr::Const(l), Expr::Const(r)) => Ok(Expr::Const(apply(l, op, r)?)),
+                    (left, right) => Ok(Expr::Op(Box::new(left), *op, Box::new(right))),
+                }
             }
         }
     }
+
+    fn linear(&self) -> Result<Linear, SolveError> {
+        match self {
+            Expr::Const(value) => Ok(Linear {
+                coef: Rational::ZERO,
+                constant: *value,
+            }),
+            Expr::Unknown(_) => Ok(Linear {
+                coef: Rational::ONE,
+                constant: Rational::ZERO,
+            }),
+            Expr::Op(left, op, right) => {
+                let (l, r) = (left.linear()?, right.linear()?);
+                match op {
+                    Operation::Add => Ok(Linear {
+                        coef: l.coef + r.coef,
+                        constant: l.constant + r.constant,
+                    }),
+                    Operation::Sub => Ok(Linear {
+                        coef: l.coef - r.coef,
+                        constant: l.constant - r.constant,
+                    }),
+                    Operation::Mul if l.coef == Rational::ZERO => Ok(Linear {
+                        coef: l.constant * r.coef,
+                        constant: l.constant * r.constant,
+                    }),
+                    Operation::Mul if r.coef == Rational::ZERO => Ok(Linear {
+                        coef: l.coef * r.constant,
+                        constant: l.constant * r.constant,
+                    }),
+                    Operation::Div if r.coef == Rational::ZERO && r.constant != Rational::ZERO => {
+                        Ok(Linear {
+                            coef: l.coef / r.constant,
+                            constant: l.constant / r.constant,
+                        })
+                    }
+                    Operation::Div if r.coef == Rational::ZERO => Err(SolveError::DivisionByZero),
+                    _ => Err(SolveError::NonLinear),
+                }
+            }
+        }
+    }
+}
+
+fn apply(left: Rational, op: &Operation, right: Rational) -> Result<Rational, SolveError> {
+    Ok(match op {
+        Operation::Add => left + right,
+        Operation::Sub => left - right,
+        Operation::Mul => left * right,
+        Operation::Div if right == Rational::ZERO => return Err(SolveError::DivisionByZero),
+        Operation::Div => left / right,
+    })
 }
 
-fn contains_monkey(monkeys: &HashMap<String, Monkey>, monkey_id: &str, contains_id: &str) -> bool {
-    if monkey_id == contains_id {
-        return true;
+fn write_operand(f: &mut std::fmt::Formatter<'_>, expr: &Expr) -> std::fmt::Result {
+    match expr {
+        Expr::Op(..) => write!(f, "({expr})"),
+        _ => write!(f, "{expr}"),
     }
-    if let Some(monkey) = monkeys.get(monkey_id) {
-        let op = &monkey.op;
-        match op {
-            MonkeyOp::Operation { left, op: _, right } => {
-                if left == contains_id || right == contains_id {
-                    return true;
-                }
-                let left_val = contains_monkey(monkeys, left, contains_id);
-                let right_val = contains_monkey(monkeys, right, contains_id);
-                left_val || right_val
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Const(value) => write!(f, "{value}"),
+            Expr::Unknown(id) => write!(f, "{id}"),
+            Expr::Op(left, op, right) => {
+                write_operand(f, left)?;
+                write!(f, " {op} ")?;
+                write_operand(f, right)
             }
-            _ => false,
         }
-    } else {
-        false
+    }
+}
+
+/// Root's two monkeys, which have to yell the same number.
+#[derive(Debug, Clone)]
+struct Equation {
+    left: Expr,
+    right: Expr,
+}
+
+impl Equation {
+    /// The value of the unknown that balances both sides.
+    fn solve(&self) -> Result<Rational, SolveError> {
+        let (l, r) = (self.left.linear()?, self.right.linear()?);
+        // l.coef * x + l.constant = r.coef * x + r.constant
+        let coef = l.coef - r.coef;
+        let constant = r.constant - l.constant;
+        match (coef == Rational::ZERO, constant == Rational::ZERO) {
+            (false, _) => Ok(constant / coef),
+            (true, true) => Err(SolveError::Ambiguous),
+            (true, false) => Err(SolveError::NoSolution),
+        }
+    }
+}
+
+impl Display for Equation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.left, self.right)
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    input: HashMap<String, Monkey>,
+}
+
+impl Solution {
+    /// Root's check compiled into an equation in `unknown`.
+    fn equation(&self, unknown: &str) -> Result<Equation, SolveError> {
+        let root = self
+            .input
+            .get("root")
+            .ok_or_else(|| SolveError::MissingMonkey("root".to_string()))?;
+        let MonkeyOp::Operation { left, right, .. } = &root.op else {
+            return Err(SolveError::NotAnEquation);
+        };
+        if !self.input.contains_key(unknown) {
+            return Err(SolveError::MissingMonkey(unknown.to_string()));
+        }
+
+        Ok(Equation {
+            left: Expr::compile(&self.input, left, Some(unknown))?,
+            right: Expr::compile(&self.input, right, Some(unknown))?,
+        })
     }
 }
 
@@ -120,51 +280,16 @@ impl Solver for Solution {
     }
 
     fn solve_part1(&self) -> String {
-        resolve_monkey(&self.input, "root").to_string()
+        match Expr::compile(&self.input, "root", None) {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.to_string(),
+        }
     }
 
     fn solve_part2(&self) -> String {
-        let root = self.input.get("root").unwrap();
-        if let MonkeyOp::Operation { left, op: _, right } = &root.op {
-            let (mut contains_humn, other_side) = if contains_monkey(&self.input, left, "humn") {
-                (left, right)
-            } else {
-                (right, left)
-            };
-
-            let mut other_val = resolve_monkey(&self.input, other_side);
-            loop {
-                let humn_side = self.input.get(contains_humn).unwrap();
-                match &humn_side.op {
-                    MonkeyOp::Value(_) => {
-                        assert_eq!(humn_side.id, "humn".to_string());
-                        return other_val.to_string();
-                    }
-                    MonkeyOp::Operation { left, op, right } => {
-                        contains_humn = if contains_monkey(&self.input, left, "humn") {
-                            let right_val = resolve_monkey(&self.input, right);
-                            match op {
-                                Operation::Add => other_val -= right_val,
-                                Operation::Sub => other_val += right_val,
-                                Operation::Div => other_val *= right_val,
-                                Operation::Mul => other_val /= right_val,
-                            }
-                            left
-                        } else {
-                            let left_val = resolve_monkey(&self.input, left);
-                            match op {
-                                Operation::Add => other_val -= left_val,
-                                Operation::Sub => other_val = -(other_val - left_val),
-                                Operation::Div => other_val = left_val / other_val,
-                                Operation::Mul => other_val /= left_val,
-                            }
-                            right
-                        };
-                    }
-                }
-            }
-        } else {
-            "wrong root".to_string()
+        match self.equation("humn").and_then(|eq| eq.solve()) {
+            Ok(humn) => humn.to_string(),
+            Err(err) => err.to_string(),
         }
     }
 }
@@ -208,6 +333,48 @@ hmdt: 32"
         assert_eq!(solution, "301");
     }
 
+    #[test]
+    fn test_equation() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let equation = solver.equation("humn").unwrap();
+        assert_eq!(equation.to_string(), "(4 + (2 * (humn - 3))) / 4 = 150");
+
+        let solve_for = |id| solver.equation(id).and_then(|eq| eq.solve());
+        assert_eq!(solve_for("sllz"), Ok(Rational::from(596i64)));
+        assert_eq!(solve_for("dbpl"), Ok(Rational::new(1, 15)));
+        assert_eq!(
+            solve_for("nope"),
+            Err(SolveError::MissingMonkey("nope".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_unsolvable_equations() {
+        let solve = |input: &str| {
+            let mut solver = Solution::default();
+            solver.with_input(input);
+            solver.equation("humn").and_then(|eq| eq.solve())
+        };
+        let base = "root: left + right\nhumn: 5\n";
+        assert_eq!(
+            solve(&format!("{base}left: humn * humn\nright: 4")),
+            Err(SolveError::NonLinear)
+        );
+        assert_eq!(
+            solve(&format!("{base}left: four / humn\nfour: 4\nright: 4")),
+            Err(SolveError::NonLinear)
+        );
+        assert_eq!(
+            solve(&format!("{base}left: humn - humn\nright: 0")),
+            Err(SolveError::Ambiguous)
+        );
+        assert_eq!(
+            solve(&format!("{base}left: humn - humn\nright: 1")),
+            Err(SolveError::NoSolution)
+        );
+    }
+
     #[test]
     fn test_solution_part1() {
         let mut solver = Solution::default();
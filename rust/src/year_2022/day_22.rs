@@ -0,0 +1,328 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{solver::Solver, utils::grid::Grid};
+
+const FACINGS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Forward(usize),
+    Left,
+    Right,
+}
+
+type Vec3 = [i32; 3];
+
+fn neg(v: Vec3) -> Vec3 {
+    [-v[0], -v[1], -v[2]]
+}
+
+fn dot(a: Vec3, b: Vec3) -> i32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Where a face of the net ends up once the net is folded into a cube: the
+/// outward `normal` and the 3d directions its columns (`right`) and rows
+/// (`down`) run in.
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    row: usize,
+    col: usize,
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Face {
+    fn facing_of(&self, dir: Vec3) -> usize {
+        [self.right, self.down, neg(self.right), neg(self.down)]
+            .iter()
+            .position(|v| *v == dir)
+            .unwrap()
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    map: Grid<u8>,
+    path: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Walker {
+    row: usize,
+    col: usize,
+    facing: usize,
+}
+
+impl Walker {
+    fn password(&self) -> usize {
+        1000 * (self.row + 1) + 4 * (self.col + 1) + self.facing
+    }
+}
+
+impl Solution {
+    fn tile(&self, row: isize, col: isize) -> u8 {
+        self.map.get_signed(row, col).copied().unwrap_or(b' ')
+    }
+
+    fn start(&self) -> Walker {
+        Walker {
+            row: 0,
+            col: self.map.row(0).iter().position(|t| *t == b'.').unwrap(),
+            facing: 0,
+        }
+    }
+
+    /// Follows the path, asking `wrap` where to end up whenever a step would
+    /// leave the board.
+    fn walk(&self, wrap: impl Fn(&Walker) -> Walker) -> Walker {
+        let mut walker = self.start();
+
+        for step in self.path.iter() {
+            match step {
+                Step::Left => walker.facing = (walker.facing + 3) % 4,
+                Step::Right => walker.facing = (walker.facing + 1) % 4,
+                Step::Forward(amt) => {
+                    for _ in 0..*amt {
+                        let (dr, dc) = FACINGS[walker.facing];
+                        let (row, col) = (walker.row as isize + dr, walker.col as isize + dc);
+                        let next = if self.tile(row, col) == b' ' {
+                            wrap(&walker)
+                        } else {
+                            Walker {
+                                row: row as usize,
+                                col: col as usize,
+                                ..walker
+                            }
+                        };
+
+                        if self.map[(next.row, next.col)] == b'#' {
+                            break;
+                        }
+                        walker = next;
+                    }
+                }
+            }
+        }
+
+        walker
+    }
+
+    fn wrap_flat(&self, walker: &Walker) -> Walker {
+        let (dr, dc) = FACINGS[walker.facing];
+        let (mut row, mut col) = (walker.row as isize, walker.col as isize);
+        while self.tile(row - dr, col - dc) != b' ' {
+            row -= dr;
+            col -= dc;
+        }
+        Walker {
+            row: row as usize,
+            col: col as usize,
+            ..*walker
+        }
+    }
+
+    fn face_size(&self) -> usize {
+        let tiles = self.map.iter().filter(|(_, t)| **t != b' ').count();
+        ((tiles / 6) as f64).sqrt() as usize
+    }
+
+    /// Folds the net by walking across its faces, rolling the 3d frame over
+    /// each shared edge.
+    fn fold(&self, size: usize) -> Vec<Face> {
+        let start = self.start();
+        let first = Face {
+            row: start.row / size,
+            col: start.col / size,
+            normal: [0, 0, 1],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        };
+
+        let mut faces = HashMap::from([((first.row, first.col), first)]);
+        let mut queue = VecDeque::from([first]);
+        while let Some(face) = queue.pop_front() {
+            let neighbours = [
+                (0, 1, face.right, neg(face.normal), face.down),
+                (0, -1, neg(face.right), face.normal, face.down),
+                (1, 0, face.down, face.right, neg(face.normal)),
+                (-1, 0, neg(face.down), face.right, face.normal),
+            ];
+            for (dr, dc, normal, right, down) in neighbours {
+                let row = face.row as isize + dr;
+                let col = face.col as isize + dc;
+                if self.tile(row * size as isize, col * size as isize) == b' ' {
+                    continue;
+                }
+                let (row, col) = (row as usize, col as usize);
+                faces.entry((row, col)).or_insert_with(|| {
+                    let next = Face {
+                        row,
+                        col,
+                        normal,
+                        right,
+                        down,
+                    };
+                    queue.push_back(next);
+                    next
+                });
+            }
+        }
+
+        faces.into_values().collect()
+    }
+
+    /// Wraps around the folded cube. Tile centres are placed in doubled
+    /// coordinates on a cube spanning `-size..=size`, so stepping over an edge
+    /// is a move of one along the old direction and one back along the old
+    /// normal.
+    fn wrap_cube(faces: &[Face], size: usize, walker: &Walker) -> Walker {
+        let n = size as i32;
+        let from = faces
+            .iter()
+            .find(|f| f.row == walker.row / size && f.col == walker.col / size)
+            .unwrap();
+
+        let i = (walker.row % size) as i32;
+        let j = (walker.col % size) as i32;
+        let dir = [from.right, from.down, neg(from.right), neg(from.down)][walker.facing];
+
+        let mut pos = [0; 3];
+        for (axis, p) in pos.iter_mut().enumerate() {
+            *p = from.normal[axis] * n
+                + from.right[axis] * (2 * j + 1 - n)
+                + from.down[axis] * (2 * i + 1 - n)
+                + dir[axis]
+                - from.normal[axis];
+        }
+
+        let to = faces.iter().find(|f| f.normal == dir).unwrap();
+        let j = ((dot(pos, to.right) + n - 1) / 2) as usize;
+        let i = ((dot(pos, to.down) + n - 1) / 2) as usize;
+
+        Walker {
+            row: to.row * size + i,
+            col: to.col * size + j,
+            facing: to.facing_of(neg(from.normal)),
+        }
+    }
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        let (map, path) = input
+            .split_once("\n\n")
+            .expect("the map and the path should be separated by a blank line");
+
+        // rows to the right of the net are left short, so pad them out
+        let width = map.lines().map(|l| l.len()).max().unwrap_or_default();
+        let padded = map
+            .lines()
+            .map(|l| format!("{l:width$}"))
+            .collect::<Vec<String>>()
+            .join("\n");
+        self.map = Grid::parse(&padded, |c| c as u8);
+
+        self.path.clear();
+        let mut amt = 0;
+        for c in path.trim().chars() {
+            match c {
+                'L' | 'R' => {
+                    self.path.push(Step::Forward(amt));
+                    self.path
+                        .push(if c == 'L' { Step::Left } else { Step::Right });
+                    amt = 0;
+                }
+                _ => {
+                    let digit = c
+                        .to_digit(10)
+                        .unwrap_or_else(|| panic!("bad path step {c}"));
+                    amt = amt * 10 + digit as usize;
+                }
+            }
+        }
+        self.path.push(Step::Forward(amt));
+    }
+
+    fn solve_part1(&self) -> String {
+        self.walk(|w| self.wrap_flat(w)).password().to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        let size = self.face_size();
+        let faces = self.fold(size);
+        self.walk(|w| Solution::wrap_cube(&faces, size, w))
+            .password()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "6032");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "5031");
+    }
+
+    #[test]
+    fn test_cube_wrap_round_trips() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let size = solver.face_size();
+        let faces = solver.fold(size);
+        assert_eq!(faces.len(), 6);
+
+        // leaving any edge and immediately turning back should land on the
+        // tile we started from
+        for ((row, col), _) in solver.map.iter().filter(|(_, t)| **t != b' ') {
+            for (facing, (dr, dc)) in FACINGS.iter().enumerate() {
+                if solver.tile(row as isize + dr, col as isize + dc) != b' ' {
+                    continue;
+                }
+                let walker = Walker { row, col, facing };
+                let mut there = Solution::wrap_cube(&faces, size, &walker);
+                there.facing = (there.facing + 2) % 4;
+                let back = Solution::wrap_cube(&faces, size, &there);
+                assert_eq!((back.row, back.col), (row, col));
+                assert_eq!(back.facing, (facing + 2) % 4);
+            }
+        }
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2022/day_22.txt");
@@ -0,0 +1,152 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::solver::Solver;
+
+type Pos = (i32, i32);
+
+const NEIGHBOURS: [Pos; 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// The four proposal directions in their initial order, each as the move
+/// itself followed by the three cells that must be empty for it.
+const PROPOSALS: [[Pos; 3]; 4] = [
+    [(-1, 0), (-1, -1), (-1, 1)],
+    [(1, 0), (1, -1), (1, 1)],
+    [(0, -1), (-1, -1), (1, -1)],
+    [(0, 1), (-1, 1), (1, 1)],
+];
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    elves: HashSet<Pos>,
+}
+
+/// Runs a single round, returning whether any elf moved.
+fn run_round(elves: &mut HashSet<Pos>, round: usize) -> bool {
+    let mut proposed: HashMap<Pos, Vec<Pos>> = HashMap::new();
+
+    for &(r, c) in elves.iter() {
+        let is_free = |(dr, dc): &Pos| !elves.contains(&(r + dr, c + dc));
+        if NEIGHBOURS.iter().all(is_free) {
+            continue;
+        }
+
+        if let Some([(dr, dc), ..]) = (0..4)
+            .map(|i| PROPOSALS[(round + i) % 4])
+            .find(|cells| cells.iter().all(is_free))
+        {
+            proposed.entry((r + dr, c + dc)).or_default().push((r, c));
+        }
+    }
+
+    let mut moved = false;
+    for (target, from) in proposed {
+        if from.len() == 1 {
+            elves.remove(&from[0]);
+            elves.insert(target);
+            moved = true;
+        }
+    }
+    moved
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.elves = input
+            .lines()
+            .enumerate()
+            .flat_map(|(r, l)| {
+                l.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(c, _)| (r as i32, c as i32))
+            })
+            .collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        let mut elves = self.elves.clone();
+        for round in 0..10 {
+            run_round(&mut elves, round);
+        }
+
+        let (Some(min_r), Some(max_r)) = (
+            elves.iter().map(|e| e.0).min(),
+            elves.iter().map(|e| e.0).max(),
+        ) else {
+            return "0".to_string();
+        };
+        let min_c = elves.iter().map(|e| e.1).min().unwrap();
+        let max_c = elves.iter().map(|e| e.1).max().unwrap();
+
+        ((max_r - min_r + 1) * (max_c - min_c + 1) - elves.len() as i32).to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        let mut elves = self.elves.clone();
+        let mut round = 0;
+        while run_round(&mut elves, round) {
+            round += 1;
+        }
+
+        (round + 1).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#.."
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "110");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "20");
+    }
+
+    #[test]
+    fn test_small_example() {
+        let mut elves = HashSet::from([(1, 2), (1, 3), (2, 2), (4, 2), (4, 3)]);
+        for round in 0..3 {
+            run_round(&mut elves, round);
+        }
+        assert_eq!(
+            elves,
+            HashSet::from([(0, 2), (1, 4), (2, 0), (3, 4), (5, 2)])
+        );
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2022/day_23.txt");
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use crate::{solver::Solver, utils::grid::Grid};
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    /// the valley interior, without the surrounding walls
+    valley: Grid<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Solution {
+    /// Blizzards wrap around the interior, so instead of simulating them we
+    /// look back along the row and column for one that would be here at `time`.
+    fn is_clear(&self, row: usize, col: usize, time: usize) -> bool {
+        let (row, col, time) = (row as isize, col as isize, time as isize);
+        self.valley.get_wrapping(row, col - time) != Some(&b'>')
+            && self.valley.get_wrapping(row, col + time) != Some(&b'<')
+            && self.valley.get_wrapping(row - time, col) != Some(&b'v')
+            && self.valley.get_wrapping(row + time, col) != Some(&b'^')
+    }
+
+    /// Breadth-first over the set of reachable cells minute by minute. `None`
+    /// stands for waiting outside the valley at the entrance or exit.
+    fn cross(&self, start_time: usize, to_exit: bool) -> usize {
+        let (start, goal) = if to_exit {
+            ((0, 0), (self.height - 1, self.width - 1))
+        } else {
+            ((self.height - 1, self.width - 1), (0, 0))
+        };
+
+        let mut frontier: HashSet<Option<(usize, usize)>> = HashSet::from([None]);
+        let mut time = start_time;
+        loop {
+            time += 1;
+            let mut next = HashSet::new();
+            for pos in frontier {
+                let Some((row, col)) = pos else {
+                    next.insert(None);
+                    if self.is_clear(start.0, start.1, time) {
+                        next.insert(Some(start));
+                    }
+                    continue;
+                };
+
+                if (row, col) == goal {
+                    // the step out of the valley is always clear
+                    return time;
+                }
+
+                let moves = [
+                    (row, col),
+                    (row.wrapping_sub(1), col),
+                    (row + 1, col),
+                    (row, col.wrapping_sub(1)),
+                    (row, col + 1),
+                ];
+                for (r, c) in moves {
+                    if r < self.height && c < self.width && self.is_clear(r, c, time) {
+                        next.insert(Some((r, c)));
+                    }
+                }
+            }
+            frontier = next;
+        }
+    }
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        let walled = Grid::parse(input, |c| c as u8);
+        assert!(
+            walled.height() >= 3 && walled.width() >= 3,
+            "the valley should be surrounded by walls"
+        );
+
+        self.height = walled.height() - 2;
+        self.width = walled.width() - 2;
+        self.valley = Grid::new(
+            self.width,
+            self.height,
+            walled
+                .rows()
+                .skip(1)
+                .take(self.height)
+                .flat_map(|row| row[1..=self.width].iter().copied())
+                .collect(),
+        );
+    }
+
+    fn solve_part1(&self) -> String {
+        self.cross(0, true).to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        let there = self.cross(0, true);
+        let back = self.cross(there, false);
+        self.cross(back, true).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "18");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "54");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2022/day_24.txt");
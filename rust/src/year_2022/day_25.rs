@@ -0,0 +1,109 @@
+use crate::solver::Solver;
+
+fn from_snafu(s: &str) -> i64 {
+    s.chars().fold(0, |acc, c| {
+        acc * 5
+            + match c {
+                '2' => 2,
+                '1' => 1,
+                '0' => 0,
+                '-' => -1,
+                '=' => -2,
+                _ => panic!("unknown SNAFU digit {c}"),
+            }
+    })
+}
+
+fn to_snafu(mut n: i64) -> String {
+    if n == 0 {
+        return "0".to_string();
+    }
+
+    let mut digits = Vec::new();
+    while n != 0 {
+        // shift each base-5 digit down by two so it lands in -2..=2
+        let digit = (n + 2).rem_euclid(5) - 2;
+        digits.push(match digit {
+            2 => '2',
+            1 => '1',
+            0 => '0',
+            -1 => '-',
+            _ => '=',
+        });
+        n = (n - digit) / 5;
+    }
+    digits.iter().rev().collect()
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    input: Vec<i64>,
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.input = input.lines().map(from_snafu).collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        to_snafu(self.input.iter().sum())
+    }
+
+    fn solve_part2(&self) -> String {
+        "".to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+1=-0-2
+12111
+2=0=
+21
+2=01
+111
+20012
+112
+1=-1=
+1-12
+12
+1=
+122"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "2=-1=0");
+    }
+
+    #[test]
+    fn test_snafu_round_trip() {
+        let pairs = [
+            (1, "1"),
+            (3, "1="),
+            (8, "2="),
+            (10, "20"),
+            (15, "1=0"),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+        ];
+        for (n, s) in pairs {
+            assert_eq!(to_snafu(n), s);
+            assert_eq!(from_snafu(s), n);
+        }
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2022/day_25.txt");
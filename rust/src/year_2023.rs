@@ -1,3 +1,19 @@
 pub mod day_01;
 pub mod day_02;
 pub mod day_03;
+// no puzzle inputs for these yet, so they're only built for their example
+// tests and aren't registered with the solver
+#[cfg(test)]
+pub mod day_04;
+#[cfg(test)]
+pub mod day_05;
+#[cfg(test)]
+pub mod day_06;
+#[cfg(test)]
+pub mod day_07;
+#[cfg(test)]
+pub mod day_08;
+#[cfg(test)]
+pub mod day_09;
+#[cfg(test)]
+pub mod day_10;
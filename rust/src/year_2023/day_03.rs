@@ -1,9 +1,126 @@
-use crate::solver::Solver;
+use std::collections::HashSet;
+
+use crate::{solver::Solver, utils::grid::Grid};
+
+#[derive(Debug, PartialEq, Eq)]
+struct Number {
+    value: u32,
+    row: usize,
+    /// first column of the number
+    start: usize,
+    /// one past the last column of the number
+    end: usize,
+}
+
+#[derive(Debug)]
+struct Symbol {
+    symbol: char,
+    row: usize,
+    col: usize,
+    /// indices into `Schematic::numbers` of every number touching this symbol,
+    /// including diagonally
+    neighbours: Vec<usize>,
+}
+
+#[derive(Default, Debug)]
+struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+}
+
+impl From<&str> for Schematic {
+    fn from(value: &str) -> Self {
+        let grid = Grid::parse(value, |c| c);
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+
+        // which number, if any, covers each cell
+        let mut owners: Grid<Option<usize>> = grid.map(|_| None);
+
+        for (row, line) in grid.rows().enumerate() {
+            let mut col = 0;
+            while col < line.len() {
+                let c = line[col];
+                if c.is_ascii_digit() {
+                    let start = col;
+                    let mut value = 0;
+                    while let Some(digit) = line.get(col).and_then(|c| c.to_digit(10)) {
+                        value = value * 10 + digit;
+                        owners[(row, col)] = Some(numbers.len());
+                        col += 1;
+                    }
+                    numbers.push(Number {
+                        value,
+                        row,
+                        start,
+                        end: col,
+                    });
+                    continue;
+                }
+
+                if c != '.' {
+                    symbols.push(Symbol {
+                        symbol: c,
+                        row,
+                        col,
+                        neighbours: Vec::new(),
+                    });
+                }
+                col += 1;
+            }
+        }
+
+        for symbol in symbols.iter_mut() {
+            let mut neighbours = grid
+                .neighbours_8((symbol.row, symbol.col))
+                .filter_map(|pos| owners[pos])
+                .collect::<Vec<usize>>();
+            neighbours.sort_unstable();
+            neighbours.dedup();
+            symbol.neighbours = neighbours;
+        }
+
+        Self { numbers, symbols }
+    }
+}
+
+impl Schematic {
+    /// Every number adjacent to at least one symbol, each counted once even if
+    /// it touches several.
+    fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        let adjacent = self
+            .symbols
+            .iter()
+            .flat_map(|s| s.neighbours.iter().copied())
+            .collect::<HashSet<usize>>();
+
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| adjacent.contains(i))
+            .map(|(_, n)| n)
+    }
+
+    /// The numbers adjacent to each `symbol`, grouped by which one of them
+    /// they touch.
+    fn numbers_adjacent_to(&self, symbol: char) -> impl Iterator<Item = Vec<&Number>> {
+        self.symbols
+            .iter()
+            .filter(move |s| s.symbol == symbol)
+            .map(|s| s.neighbours.iter().map(|i| &self.numbers[*i]).collect())
+    }
+
+    /// The product of the two numbers next to each `*` that touches exactly two.
+    fn gear_ratios(&self) -> impl Iterator<Item = u32> + '_ {
+        self.numbers_adjacent_to('*')
+            .filter(|numbers| numbers.len() == 2)
+            .map(|numbers| numbers.iter().map(|n| n.value).product())
+    }
+}
 
 #[derive(Default, Debug)]
 pub struct Solution {
-    input: Vec<Vec<String>>,
-    raw_board: Vec<Vec<usize>>,
+    input: Schematic,
 }
 
 impl Solver for Solution {
@@ -12,45 +129,19 @@ impl Solver for Solution {
     }
 
     fn with_input(&mut self, input: &str) {
-        self.input = input
-            .to_owned()
-            .lines()
-            .map(|l| {
-                let mut final_row = Vec::new();
-                l.split(".").for_each(|s| {
-                    if s.len() == 0 {
-                        final_row.push("".to_owned())
-                    } else {
-                        for _ in 0..s.len() {
-                            final_row.push(s.to_string())
-                        }
-                    }
-                });
-                final_row
-            })
-            .collect::<Vec<Vec<String>>>();
-
-        self.raw_board = input
-            .to_owned()
-            .lines()
-            .map(|l| l.chars().map(|c| c as usize).collect::<Vec<usize>>())
-            .collect::<Vec<Vec<usize>>>();
+        self.input = input.into();
     }
 
     fn solve_part1(&self) -> String {
-        let mut set = std::collections::HashSet::new();
-        self.input.iter().enumerate().for_each(|(y, row)| {
-            row.iter().enumerate().for_each(|(x, col)| {
-                if col == "$" {
-                    set.insert((x, y));
-                }
-            })
-        });
-        "".to_string()
+        self.input
+            .part_numbers()
+            .map(|n| n.value)
+            .sum::<u32>()
+            .to_string()
     }
 
     fn solve_part2(&self) -> String {
-        "".to_string()
+        self.input.gear_ratios().sum::<u32>().to_string()
     }
 }
 
@@ -76,9 +167,8 @@ mod test {
     fn test_solution_example1() {
         let mut solver = Solution::default();
         solver.with_input(get_example_input());
-        println!("{:#?}", solver.input);
         let solution = solver.solve_part1();
-        assert_eq!(solution, "");
+        assert_eq!(solution, "4361");
     }
 
     #[test]
@@ -86,7 +176,29 @@ mod test {
         let mut solver = Solution::default();
         solver.with_input(get_example_input());
         let solution = solver.solve_part2();
-        assert_eq!(solution, "");
+        assert_eq!(solution, "467835");
+    }
+
+    #[test]
+    fn test_numbers_adjacent_to() {
+        let schematic = Schematic::from(get_example_input());
+        let numbers = schematic
+            .numbers_adjacent_to('*')
+            .map(|group| group.iter().map(|n| n.value).collect::<Vec<u32>>())
+            .collect::<Vec<_>>();
+        // symbols in reading order, numbers in the order they appear
+        assert_eq!(numbers, vec![vec![467, 35], vec![617], vec![755, 598]]);
+
+        let dollar = schematic.numbers_adjacent_to('$').collect::<Vec<_>>();
+        assert_eq!(
+            dollar,
+            vec![vec![&Number {
+                value: 664,
+                row: 9,
+                start: 1,
+                end: 4
+            }]]
+        );
     }
 
     #[test]
@@ -94,7 +206,7 @@ mod test {
         let mut solver = Solution::default();
         solver.with_input(solver.get_input());
         let solution = solver.solve_part1();
-        assert_eq!(solution, "");
+        assert_eq!(solution, "554003");
     }
 
     #[test]
@@ -102,7 +214,7 @@ mod test {
         let mut solver = Solution::default();
         solver.with_input(solver.get_input());
         let solution = solver.solve_part2();
-        assert_eq!(solution, "");
+        assert_eq!(solution, "87263515");
     }
 }
 
@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+
+use crate::solver::Solver;
+
+#[derive(Debug)]
+struct Card {
+    winning: HashSet<u32>,
+    numbers: Vec<u32>,
+}
+
+impl Card {
+    fn matches(&self) -> usize {
+        self.numbers
+            .iter()
+            .filter(|n| self.winning.contains(n))
+            .count()
+    }
+}
+
+impl From<&str> for Card {
+    fn from(value: &str) -> Self {
+        let (_, numbers) = value.split_once(':').unwrap();
+        let (winning, numbers) = numbers.split_once('|').unwrap();
+        Self {
+            winning: winning
+                .split_whitespace()
+                .map(|n| n.parse().unwrap())
+                .collect(),
+            numbers: numbers
+                .split_whitespace()
+                .map(|n| n.parse().unwrap())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    input: Vec<Card>,
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.input = input.lines().map(Card::from).collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        self.input
+            .iter()
+            .map(|c| match c.matches() {
+                0 => 0,
+                n => 1 << (n - 1),
+            })
+            .sum::<u32>()
+            .to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        let mut copies = vec![1; self.input.len()];
+        for (i, card) in self.input.iter().enumerate() {
+            for j in i + 1..(i + 1 + card.matches()).min(copies.len()) {
+                copies[j] += copies[i];
+            }
+        }
+
+        copies.iter().sum::<usize>().to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "13");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "30");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2023/day_04.txt");
@@ -0,0 +1,187 @@
+use std::ops::Range;
+
+use crate::solver::Solver;
+
+#[derive(Debug)]
+struct MapRange {
+    dst: i64,
+    src: Range<i64>,
+}
+
+#[derive(Debug)]
+struct Map {
+    ranges: Vec<MapRange>,
+}
+
+impl Map {
+    fn apply(&self, value: i64) -> i64 {
+        self.ranges
+            .iter()
+            .find(|r| r.src.contains(&value))
+            .map(|r| value - r.src.start + r.dst)
+            .unwrap_or(value)
+    }
+
+    /// Maps a whole range of inputs at once, splitting it wherever it crosses
+    /// the edge of one of the map's source ranges.
+    fn apply_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        let mut unmapped = vec![range];
+        let mut mapped = Vec::new();
+
+        for r in self.ranges.iter() {
+            let offset = r.dst - r.src.start;
+            let mut remaining = Vec::new();
+            for range in unmapped {
+                let start = range.start.max(r.src.start);
+                let end = range.end.min(r.src.end);
+                if start >= end {
+                    remaining.push(range);
+                    continue;
+                }
+
+                mapped.push(start + offset..end + offset);
+                if range.start < start {
+                    remaining.push(range.start..start);
+                }
+                if end < range.end {
+                    remaining.push(end..range.end);
+                }
+            }
+            unmapped = remaining;
+        }
+
+        mapped.extend(unmapped);
+        mapped
+    }
+}
+
+impl From<&str> for Map {
+    fn from(value: &str) -> Self {
+        Self {
+            ranges: value
+                .lines()
+                .skip(1)
+                .map(|l| {
+                    let mut nums = l.split_whitespace().map(|n| n.parse::<i64>().unwrap());
+                    let dst = nums.next().unwrap();
+                    let src = nums.next().unwrap();
+                    let len = nums.next().unwrap();
+                    MapRange {
+                        dst,
+                        src: src..src + len,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    seeds: Vec<i64>,
+    maps: Vec<Map>,
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        let mut sections = input.split("\n\n");
+        self.seeds = sections
+            .next()
+            .and_then(|s| s.strip_prefix("seeds:"))
+            .map(|s| s.split_whitespace().map(|n| n.parse().unwrap()).collect())
+            .unwrap_or_default();
+        self.maps = sections.map(Map::from).collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        self.seeds
+            .iter()
+            .map(|s| self.maps.iter().fold(*s, |v, m| m.apply(v)))
+            .min()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        let seeds = self
+            .seeds
+            .chunks_exact(2)
+            .map(|s| s[0]..s[0] + s[1])
+            .collect::<Vec<Range<i64>>>();
+
+        self.maps
+            .iter()
+            .fold(seeds, |ranges, m| {
+                ranges.into_iter().flat_map(|r| m.apply_range(r)).collect()
+            })
+            .iter()
+            .map(|r| r.start)
+            .min()
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "35");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "46");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2023/day_05.txt");
@@ -0,0 +1,94 @@
+use crate::solver::Solver;
+
+/// Holding the button for `h` ms travels `h * (time - h)`, so the winning
+/// holds are the integers strictly between the roots of
+/// `h^2 - time*h + record = 0`, symmetric around `time / 2`.
+fn ways_to_win(time: u64, record: u64) -> u64 {
+    let t = time as f64;
+    let disc = t * t - 4.0 * record as f64;
+    if disc < 0.0 {
+        return 0;
+    }
+
+    // the float root only gets us close, so step onto the first winning hold
+    let mut low = ((t - disc.sqrt()) / 2.0).floor().max(0.0) as u64;
+    while low > 0 && (low - 1) * (time - low + 1) > record {
+        low -= 1;
+    }
+    while low <= time / 2 && low * (time - low) <= record {
+        low += 1;
+    }
+
+    if low > time / 2 {
+        0
+    } else {
+        time - 2 * low + 1
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    times: Vec<String>,
+    records: Vec<String>,
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        let mut lines = input
+            .lines()
+            .map(|l| l.split_whitespace().skip(1).map(|n| n.to_owned()).collect());
+        self.times = lines.next().unwrap_or_default();
+        self.records = lines.next().unwrap_or_default();
+    }
+
+    fn solve_part1(&self) -> String {
+        self.times
+            .iter()
+            .zip(self.records.iter())
+            .map(|(t, r)| ways_to_win(t.parse().unwrap(), r.parse().unwrap()))
+            .product::<u64>()
+            .to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        let (Ok(time), Ok(record)) = (self.times.concat().parse(), self.records.concat().parse())
+        else {
+            return "".to_string();
+        };
+
+        ways_to_win(time, record).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+Time:      7  15   30
+Distance:  9  40  200"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "288");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "71503");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2023/day_06.txt");
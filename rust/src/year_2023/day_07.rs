@@ -0,0 +1,152 @@
+use crate::solver::Solver;
+
+const JOKER: u8 = 11;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+#[derive(Debug)]
+struct Hand {
+    cards: [u8; 5],
+    bid: usize,
+}
+
+impl Hand {
+    fn hand_type(&self, jokers_wild: bool) -> HandType {
+        let mut counts = [0u8; 15];
+        let mut jokers = 0;
+        for card in self.cards {
+            if jokers_wild && card == JOKER {
+                jokers += 1;
+            } else {
+                counts[card as usize] += 1;
+            }
+        }
+
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        // jokers always do best by joining the largest group
+        counts[0] += jokers;
+
+        match (counts[0], counts[1]) {
+            (5, _) => HandType::FiveOfAKind,
+            (4, _) => HandType::FourOfAKind,
+            (3, 2) => HandType::FullHouse,
+            (3, _) => HandType::ThreeOfAKind,
+            (2, 2) => HandType::TwoPair,
+            (2, _) => HandType::OnePair,
+            _ => HandType::HighCard,
+        }
+    }
+
+    /// The card values used to break ties, with jokers dropping below `2`.
+    fn tie_breakers(&self, jokers_wild: bool) -> [u8; 5] {
+        self.cards
+            .map(|c| if jokers_wild && c == JOKER { 1 } else { c })
+    }
+}
+
+impl From<&str> for Hand {
+    fn from(value: &str) -> Self {
+        let (cards, bid) = value.split_once(' ').unwrap();
+        let mut hand = Hand {
+            cards: [0; 5],
+            bid: bid.parse().unwrap(),
+        };
+        for (i, c) in cards.chars().enumerate() {
+            hand.cards[i] = match c {
+                'A' => 14,
+                'K' => 13,
+                'Q' => 12,
+                'J' => JOKER,
+                'T' => 10,
+                _ => c.to_digit(10).unwrap() as u8,
+            };
+        }
+        hand
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    input: Vec<Hand>,
+}
+
+impl Solution {
+    fn total_winnings(&self, jokers_wild: bool) -> usize {
+        let mut hands = self
+            .input
+            .iter()
+            .map(|h| {
+                (
+                    (h.hand_type(jokers_wild), h.tie_breakers(jokers_wild)),
+                    h.bid,
+                )
+            })
+            .collect::<Vec<_>>();
+        hands.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        hands
+            .iter()
+            .enumerate()
+            .map(|(rank, (_, bid))| (rank + 1) * bid)
+            .sum()
+    }
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.input = input.lines().map(Hand::from).collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        self.total_winnings(false).to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        self.total_winnings(true).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "6440");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "5905");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2023/day_07.txt");
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use crate::{solver::Solver, utils::math::lcm};
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    instructions: Vec<char>,
+    nodes: HashMap<String, (String, String)>,
+}
+
+impl Solution {
+    fn steps_from(&self, start: &str, is_end: impl Fn(&str) -> bool) -> usize {
+        let mut node = start;
+        for (steps, dir) in self.instructions.iter().cycle().enumerate() {
+            if is_end(node) {
+                return steps;
+            }
+            let (left, right) = &self.nodes[node];
+            node = if *dir == 'L' { left } else { right };
+        }
+        unreachable!()
+    }
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        let mut lines = input.lines();
+        self.instructions = lines.next().unwrap_or_default().chars().collect();
+        self.nodes = lines
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let (name, next) = l.split_once(" = ").unwrap();
+                let (left, right) = next
+                    .trim_matches(|c| c == '(' || c == ')')
+                    .split_once(", ")
+                    .unwrap();
+                (name.to_owned(), (left.to_owned(), right.to_owned()))
+            })
+            .collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        if !self.nodes.contains_key("AAA") {
+            return "".to_string();
+        }
+
+        self.steps_from("AAA", |n| n == "ZZZ").to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        // every ghost's path loops back to its end node with a period equal to
+        // the steps it took to first reach it, so they all line up at the lcm
+        self.nodes
+            .keys()
+            .filter(|n| n.ends_with('A'))
+            .map(|n| self.steps_from(n, |n| n.ends_with('Z')))
+            .fold(1, lcm)
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)"
+    }
+
+    fn get_example_input2<'a>() -> &'a str {
+        "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)"
+    }
+
+    fn get_example_input3<'a>() -> &'a str {
+        "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "2");
+
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input2());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "6");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input3());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "6");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2023/day_08.txt");
@@ -0,0 +1,80 @@
+use crate::solver::Solver;
+
+/// Extrapolates one step past the end of `values` by summing the last entry
+/// of each layer of differences.
+fn extrapolate(values: &[i64]) -> i64 {
+    if values.iter().all(|v| *v == 0) {
+        return 0;
+    }
+
+    let diffs = values.windows(2).map(|w| w[1] - w[0]).collect::<Vec<i64>>();
+    values.last().unwrap() + extrapolate(&diffs)
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    input: Vec<Vec<i64>>,
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.input = input
+            .lines()
+            .map(|l| l.split_whitespace().map(|n| n.parse().unwrap()).collect())
+            .collect();
+    }
+
+    fn solve_part1(&self) -> String {
+        self.input
+            .iter()
+            .map(|v| extrapolate(v))
+            .sum::<i64>()
+            .to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        // extrapolating backwards is just extrapolating the reversed history
+        self.input
+            .iter()
+            .map(|v| {
+                let reversed = v.iter().rev().copied().collect::<Vec<i64>>();
+                extrapolate(&reversed)
+            })
+            .sum::<i64>()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "114");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "2");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2023/day_09.txt");
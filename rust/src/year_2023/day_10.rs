@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use crate::{
+    solver::Solver,
+    utils::grid::{Grid, Pos},
+};
+
+const NORTH: (isize, isize) = (-1, 0);
+const SOUTH: (isize, isize) = (1, 0);
+const EAST: (isize, isize) = (0, 1);
+const WEST: (isize, isize) = (0, -1);
+
+fn connections(pipe: u8) -> &'static [(isize, isize)] {
+    match pipe {
+        b'|' => &[NORTH, SOUTH],
+        b'-' => &[EAST, WEST],
+        b'L' => &[NORTH, EAST],
+        b'J' => &[NORTH, WEST],
+        b'7' => &[SOUTH, WEST],
+        b'F' => &[SOUTH, EAST],
+        _ => &[],
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Solution {
+    tiles: Grid<u8>,
+    start: Pos,
+}
+
+impl Solution {
+    fn tile(&self, row: isize, col: isize) -> u8 {
+        self.tiles.get_signed(row, col).copied().unwrap_or(b'.')
+    }
+
+    /// Works out which pipe is hidden under `S` from the neighbours that
+    /// connect back into it.
+    fn start_pipe(&self) -> u8 {
+        let (row, col) = (self.start.0 as isize, self.start.1 as isize);
+        let linked = [NORTH, SOUTH, EAST, WEST]
+            .into_iter()
+            .filter(|(dr, dc)| connections(self.tile(row + dr, col + dc)).contains(&(-dr, -dc)))
+            .collect::<Vec<_>>();
+
+        b"|-LJ7F"
+            .iter()
+            .copied()
+            .find(|p| connections(*p).iter().all(|c| linked.contains(c)))
+            .unwrap()
+    }
+
+    fn main_loop(&self) -> HashSet<Pos> {
+        let mut visited = HashSet::from([self.start]);
+        let mut pos = self.start;
+        let mut dir = connections(self.start_pipe())[0];
+
+        loop {
+            pos = (
+                (pos.0 as isize + dir.0) as usize,
+                (pos.1 as isize + dir.1) as usize,
+            );
+            if pos == self.start {
+                return visited;
+            }
+            visited.insert(pos);
+            dir = *connections(self.tiles[pos])
+                .iter()
+                .find(|c| **c != (-dir.0, -dir.1))
+                .unwrap();
+        }
+    }
+}
+
+impl Solver for Solution {
+    fn get_input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn with_input(&mut self, input: &str) {
+        self.tiles = Grid::parse(input, |c| c as u8);
+        self.start = self
+            .tiles
+            .iter()
+            .find(|(_, t)| **t == b'S')
+            .map(|(pos, _)| pos)
+            .expect("the map should have a start tile");
+    }
+
+    fn solve_part1(&self) -> String {
+        (self.main_loop().len() / 2).to_string()
+    }
+
+    fn solve_part2(&self) -> String {
+        let main_loop = self.main_loop();
+        let start_pipe = self.start_pipe();
+
+        // scan each row, flipping inside/outside every time we cross a loop
+        // pipe that reaches north; corners that come back the same way cancel
+        let mut enclosed = 0;
+        for (row, line) in self.tiles.rows().enumerate() {
+            let mut inside = false;
+            for (col, tile) in line.iter().enumerate() {
+                if main_loop.contains(&(row, col)) {
+                    let tile = if *tile == b'S' { start_pipe } else { *tile };
+                    if connections(tile).contains(&NORTH) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    enclosed += 1;
+                }
+            }
+        }
+
+        enclosed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_example_input<'a>() -> &'a str {
+        "\
+7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ"
+    }
+
+    fn get_example_input2<'a>() -> &'a str {
+        "\
+..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+.........."
+    }
+
+    fn get_example_input3<'a>() -> &'a str {
+        "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L"
+    }
+
+    #[test]
+    fn test_solution_example1() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input());
+        let solution = solver.solve_part1();
+        assert_eq!(solution, "8");
+    }
+
+    #[test]
+    fn test_solution_example2() {
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input2());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "4");
+
+        let mut solver = Solution::default();
+        solver.with_input(get_example_input3());
+        let solution = solver.solve_part2();
+        assert_eq!(solution, "10");
+    }
+}
+
+const INPUT: &str = include_str!("../../../inputs/2023/day_10.txt");